@@ -0,0 +1,49 @@
+use std::{hint::black_box, time::Instant};
+use crate::scan;
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// Calibration lines of `width` letters with one digit a quarter of the way
+/// in from each end, so both scanners have to walk a good part of the line.
+pub fn generate(lines: usize, width: usize) -> Vec<u8> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut text = Vec::with_capacity(lines * (width + 1));
+    for _ in 0..lines {
+        let line = text.len();
+        for _ in 0..width {
+            text.push(ALPHABET[(next() % ALPHABET.len() as u64) as usize]);
+        }
+        text[line + width / 4] = b'1' + (next() % 9) as u8;
+        text[line + width - 1 - width / 4] = b'1' + (next() % 9) as u8;
+        text.push(b'\n');
+    }
+    text
+}
+
+fn measure(name: &str, text: &[u8], scan: impl Fn(&[u8]) -> Option<(u8, u8)>) {
+    let start = Instant::now();
+    let mut sum: u64 = 0;
+    for line in text.split(|b| *b == b'\n') {
+        if let Some((first, last)) = scan(black_box(line)) {
+            sum += (first * 10 + last) as u64;
+        }
+    }
+    let elapsed = start.elapsed();
+    let throughput = text.len() as f64 / elapsed.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<8} {:>10.2?} {:>10.1} MiB/s  (sum {})", name, elapsed, throughput, sum);
+}
+
+pub fn run(lines: usize, width: usize) {
+    let text = generate(lines, width);
+    println!("{} lines of {} bytes, {} MiB", lines, width, text.len() / (1024 * 1024));
+    measure("scalar", &text, |line| {
+        Some((scan::first_digit_scalar(line)?, scan::last_digit_scalar(line)?))
+    });
+    measure("swar", &text, scan::first_last_digit);
+}
@@ -1,84 +1,288 @@
-use std::{fs::read_to_string, str::from_utf8};
+mod bench;
+mod matcher;
+mod report;
+mod scan;
+mod unicode;
+mod vocabulary;
+use std::{env, fs::read_to_string, path::PathBuf, process::exit};
+use matcher::{MatchOptions, Matcher};
+use vocabulary::Vocabulary;
 
-fn main() {
-    part1();
-    part2()
+enum Mode {
+    Solve,
+    Report { part: u8, json: bool },
+    Bench { lines: usize, width: usize },
 }
 
-fn part1() {
-    let mut res: i32 = 0;
-    for line in read_to_string("input.txt").unwrap().lines() {
-        let bytes = line.as_bytes();
-        let first = get_first_ascii_digit(bytes).unwrap().clone();
-        let second = get_last_ascii_digit(bytes).unwrap().clone();
-        let calibration = from_utf8(&[first, second]).unwrap().parse::<i32>().unwrap();
-        res += calibration;
+struct Config {
+    input: PathBuf,
+    vocabulary: Vocabulary,
+    options: MatchOptions,
+    mode: Mode,
+}
+
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config {
+            input: PathBuf::from("input.txt"),
+            vocabulary: Vocabulary::english(),
+            options: MatchOptions::default(),
+            mode: Mode::Solve,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => config.input = PathBuf::from(value(&arg, args.next())?),
+                "--vocab" => {
+                    let path = PathBuf::from(value(&arg, args.next())?);
+                    config.vocabulary = Vocabulary::from_file(&path)?;
+                }
+                "--ignore-case" => config.options.ignore_case = true,
+                "--unicode" => config.options.unicode_digits = true,
+                "report" => config.mode = Mode::Report { part: 2, json: false },
+                "--part" => match (&mut config.mode, value(&arg, args.next())?.as_str()) {
+                    (Mode::Report { part, .. }, "1") => *part = 1,
+                    (Mode::Report { part, .. }, "2") => *part = 2,
+                    _ => return Err("`--part` expects `report` and a part of 1 or 2".to_string()),
+                },
+                "bench" => config.mode = Mode::Bench { lines: 100_000, width: 1_000 },
+                "--lines" | "--width" => {
+                    let n = value(&arg, args.next())?
+                        .parse::<usize>()
+                        .map_err(|_| format!("`{}` expects a number", arg))?;
+                    match (&mut config.mode, arg.as_str()) {
+                        (Mode::Bench { lines, .. }, "--lines") => *lines = n,
+                        (Mode::Bench { width, .. }, _) if n >= 2 => *width = n,
+                        (Mode::Bench { .. }, _) => {
+                            return Err("`--width` needs room for two digits, at least 2".to_string())
+                        }
+                        _ => return Err(format!("`{}` is only valid for `bench`", arg)),
+                    }
+                }
+                "--json" => match &mut config.mode {
+                    Mode::Report { json, .. } => *json = true,
+                    _ => return Err("`--json` is only valid for `report`".to_string()),
+                },
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+        Ok(config)
     }
-    println!("{}", res)
 }
 
-fn get_first_ascii_digit(bytes: &[u8]) -> Option<&u8> {
-    match bytes {
-        [] => panic!("Digit not found!"),
-        [byte, ..] if (byte >= &b'0' && byte <= &b'9') => Some(byte),
-        [_, tail @ ..] => get_first_ascii_digit(tail), 
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or(format!("`{}` expects a value", flag))
+}
+
+fn main() {
+    let config = Config::from_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: day1 [report [--part 1|2] [--json] | bench [--lines N] [--width N]]");
+        eprintln!("            [--input FILE] [--vocab FILE] [--ignore-case] [--unicode]");
+        exit(2)
+    });
+    match config.mode {
+        Mode::Solve => {
+            match config.options.unicode_digits {
+                true => println!("{}", part1_unicode(&config.input)),
+                false => println!("{}", part1(&config.input)),
+            }
+            println!("{}", part2(&config.input, &config.vocabulary.matcher(config.options)));
+        }
+        Mode::Report { part, json } => {
+            let vocabulary = match part {
+                1 => Vocabulary::digits(),
+                _ => config.vocabulary,
+            };
+            let text = read_to_string(&config.input).unwrap();
+            let reports = report::build(&text, &vocabulary.matcher(config.options));
+            match json {
+                true => println!("{}", report::json(&reports)),
+                false => print!("{}", report::table(&reports)),
+            }
+        }
+        Mode::Bench { lines, width } => bench::run(lines, width),
     }
 }
 
-fn get_last_ascii_digit(bytes: &[u8]) -> Option<&u8> {
-    match bytes {
-        [] => None,
-        [byte] if (byte >= &b'0' && byte <= &b'9') => Some(byte),
-        [byte, tail @ ..] if (byte >= &b'0' && byte <= &b'9') => 
-            get_last_ascii_digit(tail).or(Some(byte)), 
-        [_, tail @ ..] => get_last_ascii_digit(tail), 
+fn part1(input: &PathBuf) -> i32 {
+    let mut res: i32 = 0;
+    for line in read_to_string(input).unwrap().lines() {
+        let (first, last) = scan::first_last_digit(line.as_bytes()).expect("Digit not found!");
+        res += (first * 10 + last) as i32;
     }
+    res
 }
 
-fn part2() {
+fn part1_unicode(input: &PathBuf) -> i32 {
     let mut res: i32 = 0;
-    for line in read_to_string("input.txt").unwrap().lines() {
-        let mut str = line.to_string();
-        let bytes: &mut [u8] = unsafe { str.as_bytes_mut() } ;
-        let first = get_first_digit(bytes).unwrap().clone();
-        bytes.reverse();
-        let second = get_last_digit(bytes).unwrap().clone();
-        let calibration = from_utf8(&[first, second]).unwrap().parse::<i32>().unwrap();
-        res += calibration;
+    for line in read_to_string(input).unwrap().lines() {
+        let (first, last) = unicode::first_last_digit(line).expect("Digit not found!");
+        res += (first * 10 + last) as i32;
     }
-    println!("{}", res)
+    res
 }
 
-fn get_first_digit(bytes: &[u8]) -> Option<&u8> {
-    match bytes {
-        [] => panic!("Digit not found!"),
-        [byte, ..] if (byte >= &b'0' && byte <= &b'9') => Some(byte),
-        [b'o', b'n', b'e', ..] => Some(&b'1'),
-        [b't', b'w', b'o', ..] => Some(&b'2'),
-        [b't', b'h', b'r', b'e', b'e', ..] => Some(&b'3'),
-        [b'f', b'o', b'u', b'r', ..] => Some(&b'4'),
-        [b'f', b'i', b'v', b'e', ..] => Some(&b'5'),
-        [b's', b'i', b'x', ..] => Some(&b'6'),
-        [b's', b'e', b'v', b'e', b'n', ..] => Some(&b'7'),
-        [b'e', b'i', b'g', b'h', b't', ..] => Some(&b'8'),
-        [b'n', b'i', b'n', b'e', ..] => Some(&b'9'),
-        [_, tail @ ..] => get_first_digit(tail), 
+fn part2(input: &PathBuf, matcher: &Matcher) -> i32 {
+    let mut res: i32 = 0;
+    for line in read_to_string(input).unwrap().lines() {
+        let (first, last) = matcher.first_last(line).expect("Digit not found!");
+        res += (first.value * 10 + last.value) as i32;
     }
+    res
 }
 
-fn get_last_digit(bytes: &[u8]) -> Option<&u8> {
-    match bytes {
-        [] => panic!("Digit not found!"),
-        [byte, ..] if (byte >= &b'0' && byte <= &b'9') => Some(byte),
-        [b'e', b'n', b'o', ..] => Some(&b'1'),
-        [b'o', b'w', b't', ..] => Some(&b'2'),
-        [b'e', b'e', b'r', b'h', b't', ..] => Some(&b'3'),
-        [b'r', b'u', b'o', b'f', ..] => Some(&b'4'),
-        [b'e', b'v', b'i', b'f', ..] => Some(&b'5'),
-        [b'x', b'i', b's', ..] => Some(&b'6'),
-        [b'n', b'e', b'v', b'e', b's', ..] => Some(&b'7'),
-        [b't', b'h', b'g', b'i', b'e', ..] => Some(&b'8'),
-        [b'e', b'n', b'i', b'n', ..] => Some(&b'9'),
-        [_, tail @ ..] => get_last_digit(tail), 
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use crate::{bench, matcher::MatchOptions, part1, part2, report, scan, unicode, vocabulary::Vocabulary};
+    use crate::{Config, Mode};
+
+    fn values(vocabulary: &Vocabulary, options: MatchOptions, line: &str) -> Option<(u8, u8)> {
+        let matcher = vocabulary.matcher(options);
+        matcher.first_last(line).map(|(first, last)| (first.value, last.value))
+    }
+
+    #[test]
+    fn problem_1() {
+        let path = PathBuf::from("example1.txt");
+        let sum = part1(&path);
+        assert_eq!(142, sum)
+    }
+
+    #[test]
+    fn problem_2() {
+        let path = PathBuf::from("example.txt");
+        let matcher = Vocabulary::english().matcher(MatchOptions::default());
+        let sum = part2(&path, &matcher);
+        assert_eq!(281, sum)
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let matcher = Vocabulary::english().matcher(MatchOptions::default());
+        let (first, last) = matcher.first_last("xtwone").unwrap();
+        assert_eq!((first.value, first.start), (2, 1));
+        assert_eq!((last.value, last.start), (1, 3));
+
+        let (first, last) = matcher.first_last("eighthree").unwrap();
+        assert_eq!((first.value, last.value), (8, 3));
+
+        let (first, last) = matcher.first_last("7").unwrap();
+        assert_eq!(first, last);
+        assert_eq!(matcher.first_last("abc"), None);
+    }
+
+    #[test]
+    fn custom_vocabularies() {
+        let english = Vocabulary::english();
+        let with_zero = Vocabulary::parse("# comment\n\nzero 0\none 1\n").unwrap();
+        assert_eq!(values(&english, MatchOptions::default(), "zero5"), Some((5, 5)));
+        assert_eq!(values(&with_zero, MatchOptions::default(), "zero5one"), Some((0, 1)));
+
+        let german = Vocabulary::from_file(&PathBuf::from("vocab/german.txt")).unwrap();
+        assert_eq!(values(&german, MatchOptions::default(), "xfünfzweix"), Some((5, 2)));
+
+        let swedish = Vocabulary::from_file(&PathBuf::from("vocab/swedish.txt")).unwrap();
+        assert_eq!(values(&swedish, MatchOptions::default(), "åttanio"), Some((8, 9)));
+
+        assert!(Vocabulary::parse("ten 10").is_err());
+        assert!(Vocabulary::parse("one").is_err());
+    }
+
+    #[test]
+    fn ignore_case() {
+        let english = Vocabulary::english();
+        let options = MatchOptions { ignore_case: true, ..Default::default() };
+        assert_eq!(values(&english, MatchOptions::default(), "ONE2"), Some((2, 2)));
+        assert_eq!(values(&english, options, "ONE2sEvEn"), Some((1, 7)));
+
+        let swedish = Vocabulary::from_file(&PathBuf::from("vocab/swedish.txt")).unwrap();
+        let matcher = swedish.matcher(options);
+        let (first, last) = matcher.first_last("xÅTTA..TVÅ").unwrap();
+        assert_eq!((first.value, first.start, first.end), (8, 1, 6));
+        assert_eq!((last.value, last.start, last.end), (2, 8, 12));
+    }
+
+    #[test]
+    fn calibration_report() {
+        let text = "two1nine\nnothing here\n\"7\"";
+        let matcher = Vocabulary::english().matcher(MatchOptions::default());
+        let reports = report::build(text, &matcher);
+        assert_eq!(reports.iter().map(|r| r.value()).collect::<Vec<_>>(), vec![Some(29), None, Some(77)]);
+
+        let table = report::table(&reports);
+        assert!(table.contains("two@0..3"));
+        assert!(table.contains("nine@4..8"));
+        assert!(table.contains("no digits"));
+        assert!(table.ends_with("total: 106 (1 lines without digits)\n"));
+
+        assert_eq!(
+            report::json(&reports[1..]),
+            concat!(
+                "[{\"line\":2,\"first\":null,\"last\":null,\"value\":null},",
+                "{\"line\":3,\"first\":{\"token\":\"7\",\"start\":1,\"end\":2,\"digit\":7},",
+                "\"last\":{\"token\":\"7\",\"start\":1,\"end\":2,\"digit\":7},\"value\":77}]"
+            )
+        );
+    }
+
+    #[test]
+    fn unicode_digits() {
+        assert_eq!(unicode::decimal_value('٣'), Some(3));
+        assert_eq!(unicode::decimal_value('७'), Some(7));
+        assert_eq!(unicode::decimal_value('０'), Some(0));
+        assert_eq!(unicode::decimal_value('𝟗'), Some(9));
+        assert_eq!(unicode::decimal_value('Ⅷ'), None);
+        assert_eq!(unicode::decimal_value('½'), None);
+        assert_eq!(unicode::decimal_value('a'), None);
+
+        assert_eq!(unicode::first_last_digit("x٣abc७y"), Some((3, 7)));
+        assert_eq!(unicode::first_last_digit("１２３"), Some((1, 3)));
+        assert_eq!(unicode::first_last_digit("ab4ɛ"), Some((4, 4)));
+        assert_eq!(unicode::first_last_digit("Ⅻ³"), None);
+
+        let english = Vocabulary::english();
+        let options = MatchOptions { unicode_digits: true, ..Default::default() };
+        assert_eq!(values(&english, MatchOptions::default(), "one٥"), Some((1, 1)));
+        assert_eq!(values(&english, options, "one٥"), Some((1, 5)));
+        assert_eq!(values(&english, options, "９ｔｗｏtwo"), Some((9, 2)));
+
+        let matcher = english.matcher(options);
+        let (first, last) = matcher.first_last("ä٥ü").unwrap();
+        assert_eq!((first.start, first.end, first.value), (2, 4, 5));
+        assert_eq!(first, last);
+    }
+
+    #[test]
+    fn word_scanner() {
+        assert_eq!(scan::first_last_digit(b"treb7uchet"), Some((7, 7)));
+        assert_eq!(scan::first_last_digit(b"abcdefghijklmnop"), None);
+        assert_eq!(scan::first_last_digit(b""), None);
+        assert_eq!(scan::first_last_digit(b"\xc3\xa9/:9\xff"), Some((9, 9)));
+
+        let mut text = bench::generate(200, 37);
+        text.extend_from_slice(b"\xb0\xb9/:0\xff\x00\x7f.;:/,9\xf0\x30\xb1\x80\x39x\n");
+        for line in text.split(|b| *b == b'\n') {
+            for len in 0..line.len() {
+                let line = &line[len..];
+                assert_eq!(scan::first_digit(line), scan::first_digit_scalar(line));
+                assert_eq!(scan::last_digit(line), scan::last_digit_scalar(line));
+            }
+        }
+    }
+
+    #[test]
+    fn bench_arguments() {
+        let args = |args: &[&str]| Config::from_args(args.iter().map(|a| a.to_string()));
+        assert!(matches!(
+            args(&["bench", "--lines", "3", "--width", "2"]).map(|c| c.mode),
+            Ok(Mode::Bench { lines: 3, width: 2 })
+        ));
+        assert!(args(&["bench", "--width", "0"]).is_err());
+        assert!(args(&["bench", "--width", "1"]).is_err());
+        assert!(args(&["--width", "10"]).is_err());
+        let text = bench::generate(2, 2);
+        assert_eq!(text.len(), 6);
+        assert!(text.split(|b| *b == b'\n').take(2).all(|line| scan::first_last_digit(line).is_some()));
     }
 }
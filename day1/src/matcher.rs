@@ -0,0 +1,184 @@
+use crate::unicode::decimal_value;
+
+const ALPHABET: usize = 256;
+const ROOT: usize = 0;
+const NONE: u32 = u32::MAX;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MatchOptions {
+    /// Compare letters case-insensitively, for both ASCII and other scripts.
+    pub ignore_case: bool,
+    /// Also accept non-ASCII decimal digits such as `٣` or `７`.
+    pub unicode_digits: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub value: u8,
+}
+
+struct State {
+    next: [u32; ALPHABET],
+    /// Pattern ending in this state, as `(length, value)`.
+    output: Option<(usize, u8)>,
+    /// Closest state on the failure chain that also ends a pattern.
+    dict: Option<u32>,
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            next: [NONE; ALPHABET],
+            output: None,
+            dict: None,
+        }
+    }
+}
+
+/// Aho–Corasick automaton over a set of digit tokens. The transition table is
+/// fully expanded, so scanning a line is a single forward pass with no
+/// backtracking and no allocation, and overlapping tokens ("twone") are all
+/// reported.
+pub struct Matcher {
+    states: Vec<State>,
+    options: MatchOptions,
+}
+
+impl Matcher {
+    pub fn new<'a>(
+        patterns: impl IntoIterator<Item = (&'a str, u8)>,
+        options: MatchOptions,
+    ) -> Self {
+        let mut states = vec![State::new()];
+        for (pattern, value) in patterns {
+            let pattern: String = match options.ignore_case {
+                true => pattern.chars().map(fold).collect(),
+                false => pattern.to_string(),
+            };
+            let mut state = ROOT;
+            for byte in pattern.bytes() {
+                state = match states[state].next[byte as usize] {
+                    NONE => {
+                        states.push(State::new());
+                        let child = states.len() - 1;
+                        states[state].next[byte as usize] = child as u32;
+                        child
+                    }
+                    child => child as usize,
+                };
+            }
+            if state != ROOT && states[state].output.is_none() {
+                states[state].output = Some((pattern.len(), value));
+            }
+        }
+
+        let mut fail = vec![ROOT; states.len()];
+        let mut queue = std::collections::VecDeque::new();
+        for byte in 0..ALPHABET {
+            match states[ROOT].next[byte] {
+                NONE => states[ROOT].next[byte] = ROOT as u32,
+                child => queue.push_back(child as usize),
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            for byte in 0..ALPHABET {
+                let fallback = states[fail[state]].next[byte];
+                match states[state].next[byte] {
+                    NONE => states[state].next[byte] = fallback,
+                    child => {
+                        let child = child as usize;
+                        let suffix = fallback as usize;
+                        fail[child] = suffix;
+                        states[child].dict = match states[suffix].output {
+                            Some(_) => Some(suffix as u32),
+                            None => states[suffix].dict,
+                        };
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+        Matcher { states, options }
+    }
+
+    /// Calls `f` for every token occurrence, in order of end position.
+    pub fn for_each_match(&self, haystack: &str, mut f: impl FnMut(Match)) {
+        let mut state = ROOT;
+        if !self.options.ignore_case && !self.options.unicode_digits {
+            for (idx, byte) in haystack.bytes().enumerate() {
+                state = self.step(state, byte, idx, &mut f);
+            }
+            return;
+        }
+        let mut buf = [0; 4];
+        for (offset, c) in haystack.char_indices() {
+            let folded = match self.options.ignore_case {
+                true => fold(c),
+                false => c,
+            };
+            for (i, byte) in folded.encode_utf8(&mut buf).bytes().enumerate() {
+                state = self.step(state, byte, offset + i, &mut f);
+            }
+            if !self.options.unicode_digits || c.is_ascii() {
+                continue;
+            }
+            if let Some(value) = decimal_value(c) {
+                f(Match {
+                    start: offset,
+                    end: offset + c.len_utf8(),
+                    value,
+                });
+            }
+        }
+    }
+
+    fn step(&self, state: usize, byte: u8, idx: usize, f: &mut impl FnMut(Match)) -> usize {
+        let state = self.states[state].next[byte as usize] as usize;
+        let mut out = match self.states[state].output {
+            Some(_) => Some(state as u32),
+            None => self.states[state].dict,
+        };
+        while let Some(s) = out {
+            let s = &self.states[s as usize];
+            let (len, value) = s.output.unwrap();
+            f(Match {
+                start: idx + 1 - len,
+                end: idx + 1,
+                value,
+            });
+            out = s.dict;
+        }
+        state
+    }
+
+    /// Returns the tokens starting furthest to the left and to the right.
+    /// Ties on the start position go to the longer token.
+    pub fn first_last(&self, haystack: &str) -> Option<(Match, Match)> {
+        let mut first: Option<Match> = None;
+        let mut last: Option<Match> = None;
+        self.for_each_match(haystack, |m| {
+            match first {
+                Some(f) if f.start < m.start || (f.start == m.start && f.end >= m.end) => {}
+                _ => first = Some(m),
+            }
+            match last {
+                Some(l) if l.start > m.start || (l.start == m.start && l.end >= m.end) => {}
+                _ => last = Some(m),
+            }
+        });
+        first.zip(last)
+    }
+}
+
+/// Simple lowercase folding. Characters whose lowercase form is longer or
+/// shorter in UTF-8 are left alone, so byte offsets in the folded text are
+/// the same as in the original line.
+fn fold(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) if l.len_utf8() == c.len_utf8() => l,
+        _ => c,
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt::Write;
+use crate::matcher::{Match, Matcher};
+
+/// How a single calibration line was read.
+pub struct LineReport<'a> {
+    pub line: usize,
+    pub text: &'a str,
+    pub tokens: Option<(Match, Match)>,
+}
+
+impl LineReport<'_> {
+    pub fn value(&self) -> Option<u32> {
+        self.tokens
+            .map(|(first, last)| (first.value * 10 + last.value) as u32)
+    }
+}
+
+pub fn build<'a>(text: &'a str, matcher: &Matcher) -> Vec<LineReport<'a>> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| LineReport {
+            line: idx + 1,
+            text: line,
+            tokens: matcher.first_last(line),
+        })
+        .collect()
+}
+
+pub fn table(reports: &[LineReport]) -> String {
+    let mut out = String::new();
+    let cell = |text: &str, m: &Match| format!("{}@{}..{}", &text[m.start..m.end], m.start, m.end);
+    writeln!(out, "{:>6}  {:<16}  {:<16}  value", "line", "first", "last").unwrap();
+    for report in reports {
+        match report.tokens {
+            Some((first, last)) => writeln!(
+                out,
+                "{:>6}  {:<16}  {:<16}  {}",
+                report.line,
+                cell(report.text, &first),
+                cell(report.text, &last),
+                report.value().unwrap()
+            ),
+            None => writeln!(out, "{:>6}  {:<16}  {:<16}  no digits", report.line, "-", "-"),
+        }
+        .unwrap();
+    }
+    let total: u32 = reports.iter().filter_map(|r| r.value()).sum();
+    let missing = reports.iter().filter(|r| r.tokens.is_none()).count();
+    writeln!(out, "total: {} ({} lines without digits)", total, missing).unwrap();
+    out
+}
+
+pub fn json(reports: &[LineReport]) -> String {
+    let token = |text: &str, m: &Match| {
+        format!(
+            "{{\"token\":{},\"start\":{},\"end\":{},\"digit\":{}}}",
+            json_string(&text[m.start..m.end]),
+            m.start,
+            m.end,
+            m.value
+        )
+    };
+    let lines: Vec<String> = reports
+        .iter()
+        .map(|report| match report.tokens {
+            Some((first, last)) => format!(
+                "{{\"line\":{},\"first\":{},\"last\":{},\"value\":{}}}",
+                report.line,
+                token(report.text, &first),
+                token(report.text, &last),
+                report.value().unwrap()
+            ),
+            None => format!(
+                "{{\"line\":{},\"first\":null,\"last\":null,\"value\":null}}",
+                report.line
+            ),
+        })
+        .collect();
+    format!("[{}]", lines.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
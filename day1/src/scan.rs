@@ -0,0 +1,57 @@
+const WORD: usize = 8;
+const ONES: u64 = u64::MAX / 255;
+const LOW: u64 = ONES * 0x7f;
+const HIGH: u64 = ONES * 0x80;
+
+/// Marks the high bit of every byte in `word` that is an ASCII digit. The
+/// bytes are masked to seven bits first so no lane can borrow from or carry
+/// into its neighbour, which makes the result exact per byte.
+fn digit_lanes(word: u64) -> u64 {
+    let low = word & LOW;
+    let below_colon = ONES * (0x7f + b':' as u64) - low;
+    let above_slash = low + ONES * (0x7f - b'/' as u64);
+    below_colon & !word & above_slash & HIGH
+}
+
+/// Value of the first ASCII digit in `bytes`, scanning eight bytes at a time
+/// from the front.
+pub fn first_digit(bytes: &[u8]) -> Option<u8> {
+    let mut chunks = bytes.chunks_exact(WORD);
+    for (idx, chunk) in chunks.by_ref().enumerate() {
+        let lanes = digit_lanes(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if lanes != 0 {
+            let pos = idx * WORD + lanes.trailing_zeros() as usize / 8;
+            return Some(bytes[pos] - b'0');
+        }
+    }
+    first_digit_scalar(chunks.remainder())
+}
+
+/// Value of the last ASCII digit in `bytes`, scanning eight bytes at a time
+/// from the back.
+pub fn last_digit(bytes: &[u8]) -> Option<u8> {
+    let mut chunks = bytes.rchunks_exact(WORD);
+    for (idx, chunk) in chunks.by_ref().enumerate() {
+        let lanes = digit_lanes(u64::from_le_bytes(chunk.try_into().unwrap()));
+        if lanes != 0 {
+            let start = bytes.len() - (idx + 1) * WORD;
+            let pos = start + WORD - 1 - lanes.leading_zeros() as usize / 8;
+            return Some(bytes[pos] - b'0');
+        }
+    }
+    last_digit_scalar(chunks.remainder())
+}
+
+pub fn first_last_digit(bytes: &[u8]) -> Option<(u8, u8)> {
+    Some((first_digit(bytes)?, last_digit(bytes)?))
+}
+
+/// Byte-at-a-time reference scanners, used for the tails of the word-wise
+/// scanners and as the baseline in the benchmark.
+pub fn first_digit_scalar(bytes: &[u8]) -> Option<u8> {
+    bytes.iter().find(|b| b.is_ascii_digit()).map(|b| b - b'0')
+}
+
+pub fn last_digit_scalar(bytes: &[u8]) -> Option<u8> {
+    bytes.iter().rev().find(|b| b.is_ascii_digit()).map(|b| b - b'0')
+}
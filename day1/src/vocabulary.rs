@@ -0,0 +1,69 @@
+use std::{fs::read_to_string, path::Path};
+use crate::matcher::{MatchOptions, Matcher};
+
+const ENGLISH: [(&str, u8); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+const DIGITS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+/// Table of spelled-out digit words. The ASCII digits `0`-`9` are always
+/// recognized on top of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vocabulary {
+    words: Vec<(String, u8)>,
+}
+
+impl Vocabulary {
+    /// The puzzle's vocabulary, "one" through "nine".
+    pub fn english() -> Self {
+        Vocabulary {
+            words: ENGLISH.iter().map(|(w, v)| (w.to_string(), *v)).collect(),
+        }
+    }
+
+    /// No words at all, only the ASCII digits. This is what part 1 reads.
+    pub fn digits() -> Self {
+        Vocabulary { words: Vec::new() }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{}:{}", path.display(), e))
+    }
+
+    /// Parses one `<word> <digit>` pair per line. Blank lines and lines
+    /// starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut words = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (word, value) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(word), Some(value), None) => (word, value),
+                _ => return Err(format!("{}: expected `<word> <digit>`", idx + 1)),
+            };
+            match value.parse::<u8>() {
+                Ok(value) if value <= 9 => words.push((word.to_string(), value)),
+                _ => return Err(format!("{}: `{}` is not a digit", idx + 1, value)),
+            }
+        }
+        Ok(Vocabulary { words })
+    }
+
+    pub fn matcher(&self, options: MatchOptions) -> Matcher {
+        let digits = DIGITS.iter().zip(0..).map(|(d, v)| (*d, v));
+        let words = self.words.iter().map(|(w, v)| (w.as_str(), *v));
+        Matcher::new(digits.chain(words), options)
+    }
+}
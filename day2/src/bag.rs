@@ -0,0 +1,23 @@
+use std::{fs::read_to_string, path::Path};
+use crate::{
+    cubes::{Cubes, Palette},
+    game::parse_round,
+};
+
+/// The bag from the puzzle statement.
+pub const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+/// Parses a bag written like a round, e.g. `12 red, 13 green, 14 blue`.
+pub fn parse_bag(spec: &str, palette: &mut Palette) -> Result<Cubes, String> {
+    parse_round(spec, palette).map_err(|e| format!("bag `{}`: {}", spec, e.message))
+}
+
+/// Reads one bag per line, skipping blank lines and `#` comments.
+pub fn parse_bags_file(path: &Path, palette: &mut Palette) -> Result<Vec<(String, Cubes)>, String> {
+    let text = read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| Ok((line.to_string(), parse_bag(line, palette)?)))
+        .collect()
+}
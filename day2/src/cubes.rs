@@ -0,0 +1,97 @@
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Color(usize);
+
+/// Interns color names, so cube counts are keyed by a small id and every
+/// game shares one copy of each name.
+#[derive(Default, Debug, Clone)]
+pub struct Palette {
+    names: Vec<String>,
+    ids: HashMap<String, Color>,
+}
+
+impl Palette {
+    pub fn intern(&mut self, name: &str) -> Color {
+        if let Some(color) = self.ids.get(name) {
+            return *color;
+        }
+        let color = Color(self.names.len());
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), color);
+        color
+    }
+
+    pub fn get(&self, name: &str) -> Option<Color> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, color: Color) -> &str {
+        &self.names[color.0]
+    }
+
+    /// Every color seen so far, in the order they were first seen.
+    pub fn colors(&self) -> impl Iterator<Item = Color> {
+        (0..self.names.len()).map(Color)
+    }
+}
+
+/// A handful of cubes: one round of a game, or the contents of a bag.
+/// Colors that are not present count as zero.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Cubes {
+    counts: BTreeMap<Color, usize>,
+}
+
+impl Cubes {
+    pub fn get(&self, color: Color) -> usize {
+        self.counts.get(&color).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, color: Color, count: usize) {
+        self.counts.insert(color, count);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Color, usize)> + '_ {
+        self.counts.iter().map(|(color, count)| (*color, *count))
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Lists the counts as `3 blue, 4 red`, in palette order.
+    pub fn describe(&self, palette: &Palette) -> String {
+        self.iter()
+            .map(|(color, count)| format!("{} {}", count, palette.name(color)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether these cubes could have been drawn from `bag`.
+    pub fn fits_in(&self, bag: &Cubes) -> bool {
+        self.iter().all(|(color, count)| count <= bag.get(color))
+    }
+
+    /// Per-color maximum of both handfuls.
+    pub fn union(&self, other: &Cubes) -> Cubes {
+        let mut res = self.clone();
+        for (color, count) in other.iter() {
+            if count > res.get(color) {
+                res.set(color, count);
+            }
+        }
+        res
+    }
+
+    /// Product of the counts over every color in `palette`, so a color that
+    /// is missing here makes the power zero.
+    pub fn power(&self, palette: &Palette) -> usize {
+        self.power_over(palette.colors())
+    }
+
+    /// Product of the counts over `colors` only.
+    pub fn power_over(&self, colors: impl Iterator<Item = Color>) -> usize {
+        colors.map(|color| self.get(color)).product()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    cubes::{Cubes, Palette},
+    game::{self, Game},
+    json::Json,
+};
+
+/// A round's counts keyed by color name and sorted by it, so the output does
+/// not depend on the order colors happened to be interned in.
+fn by_name<'a>(round: &Cubes, palette: &'a Palette) -> Vec<(&'a str, usize)> {
+    let mut counts: Vec<_> = round.iter().map(|(color, count)| (palette.name(color), count)).collect();
+    counts.sort();
+    counts
+}
+
+/// Canonical text for a game: `Game 1: 3 blue, 4 red; 1 red`, with single
+/// spaces and each round's colors sorted by name.
+pub fn format_game(game: &Game, palette: &Palette) -> String {
+    let rounds: Vec<String> = game
+        .rounds
+        .iter()
+        .map(|round| {
+            by_name(round, palette)
+                .iter()
+                .map(|(name, count)| format!("{} {}", count, name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .collect();
+    match rounds.is_empty() {
+        true => format!("Game {}:", game.id),
+        false => format!("Game {}: {}", game.id, rounds.join("; ")),
+    }
+}
+
+pub fn format_games(games: &[Game], palette: &Palette) -> String {
+    games
+        .iter()
+        .map(|game| format_game(game, palette) + "\n")
+        .collect()
+}
+
+/// Exports games as `[{"id":1,"rounds":[{"blue":3,"red":4}, ...]}, ...]`,
+/// with each round's colors sorted by name.
+pub fn to_json(games: &[Game], palette: &Palette) -> String {
+    let games = games
+        .iter()
+        .map(|game| {
+            let rounds = game
+                .rounds
+                .iter()
+                .map(|round| {
+                    Json::Object(
+                        by_name(round, palette)
+                            .into_iter()
+                            .map(|(name, count)| (name.to_string(), Json::Number(count)))
+                            .collect(),
+                    )
+                })
+                .collect();
+            Json::Object(vec![
+                ("id".to_string(), Json::Number(game.id)),
+                ("rounds".to_string(), Json::Array(rounds)),
+            ])
+        })
+        .collect();
+    let mut out = String::new();
+    Json::Array(games).write(&mut out);
+    out
+}
+
+/// Imports games written by [`to_json`].
+pub fn from_json(text: &str, palette: &mut Palette) -> Result<Vec<Game>, String> {
+    let Json::Array(games) = Json::parse(text)? else {
+        return Err("expected an array of games".to_string());
+    };
+    games
+        .iter()
+        .enumerate()
+        .map(|(idx, game)| parse_game(game, palette).map_err(|e| format!("game #{}: {}", idx + 1, e)))
+        .collect()
+}
+
+fn parse_game(game: &Json, palette: &mut Palette) -> Result<Game, String> {
+    let Json::Object(fields) = game else {
+        return Err("expected an object".to_string());
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+            .ok_or(format!("missing `{}`", name))
+    };
+    let Json::Number(id) = field("id")? else {
+        return Err("`id` must be a number".to_string());
+    };
+    let Json::Array(rounds) = field("rounds")? else {
+        return Err("`rounds` must be an array".to_string());
+    };
+    let rounds = rounds
+        .iter()
+        .map(|round| {
+            let Json::Object(counts) = round else {
+                return Err("each round must be an object".to_string());
+            };
+            if counts.is_empty() {
+                return Err("round has no cubes".to_string());
+            }
+            let mut cubes = Cubes::default();
+            for (name, count) in counts {
+                let Json::Number(count) = count else {
+                    return Err(format!("count of `{}` must be a number", name));
+                };
+                if !game::is_color_name(name) {
+                    return Err(format!("`{}` is not a color name the text format can read", name));
+                }
+                let color = palette.intern(name);
+                if cubes.iter().any(|(c, _)| c == color) {
+                    return Err(format!("color `{}` appears twice in one round", name));
+                }
+                cubes.set(color, *count);
+            }
+            Ok(cubes)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Game { id: *id, rounds })
+}
@@ -0,0 +1,201 @@
+use std::fmt;
+use crate::cubes::{Cubes, Palette};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: usize,
+    pub rounds: Vec<Cubes>,
+}
+
+impl Game {
+    /// Whether every round could have been drawn from `bag`.
+    pub fn is_possible(&self, bag: &Cubes) -> bool {
+        self.rounds.iter().all(|round| round.fits_in(bag))
+    }
+
+    /// The smallest bag this game could have been played with.
+    pub fn min_bag(&self) -> Cubes {
+        self.rounds
+            .iter()
+            .fold(Cubes::default(), |min_bag, round| min_bag.union(round))
+    }
+}
+
+/// Where and why a game log failed to parse. Lines and columns start at 1
+/// and columns count bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every non-blank line as a game.
+pub fn parse_games(text: &str, palette: &mut Palette) -> Result<Vec<Game>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| parse_game(line, idx + 1, palette))
+        .collect()
+}
+
+/// Parses `Game <id>: <count> <color>, ...; ...`. Whitespace is free-form
+/// and empty rounds left by trailing `,` or `;` are dropped.
+pub fn parse_game(text: &str, line: usize, palette: &mut Palette) -> Result<Game, ParseError> {
+    let mut cursor = Cursor { text, pos: 0, line };
+    cursor.skip_whitespace();
+    cursor.keyword("Game")?;
+    cursor.skip_whitespace();
+    let id = cursor.number()?;
+    cursor.skip_whitespace();
+    cursor.expect(b':')?;
+    let rounds = cursor.rounds(palette)?;
+    Ok(Game { id, rounds })
+}
+
+/// Parses a single round such as `12 red, 13 green, 14 blue`.
+pub fn parse_round(text: &str, palette: &mut Palette) -> Result<Cubes, ParseError> {
+    let mut cursor = Cursor { text, pos: 0, line: 1 };
+    let mut rounds = cursor.rounds(palette)?;
+    match rounds.len() {
+        1 => Ok(rounds.remove(0)),
+        0 => Err(cursor.error("expected at least one `<count> <color>`")),
+        _ => Err(cursor.error("expected a single round without `;`")),
+    }
+}
+
+/// Whether `name` reads back as a single color: it must be non-empty and
+/// free of whitespace and the separators `,`, `;` and `:`.
+pub fn is_color_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(is_name_byte)
+}
+
+fn is_name_byte(b: u8) -> bool {
+    !b.is_ascii_whitespace() && !b",;:".contains(&b)
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl Cursor<'_> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.pos + 1,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(b) if b == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.error(format!("expected `{}`", byte as char))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        match self.text[self.pos..].starts_with(keyword) {
+            true => {
+                self.pos += keyword.len();
+                Ok(())
+            }
+            false => Err(self.error(format!("expected `{}`", keyword))),
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        let start = self.pos;
+        let mut value: usize = 0;
+        while let Some(b) = self.peek().filter(u8::is_ascii_digit) {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((b - b'0') as usize))
+                .ok_or_else(|| ParseError {
+                    column: start + 1,
+                    ..self.error("number does not fit in usize")
+                })?;
+            self.pos += 1;
+        }
+        match self.pos > start {
+            true => Ok(value),
+            false => Err(self.error("expected a number")),
+        }
+    }
+
+    /// A color name: everything up to the next whitespace or separator.
+    fn word(&mut self) -> Result<&str, ParseError> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_name_byte) {
+            self.pos += 1;
+        }
+        match self.pos > start {
+            true => Ok(&self.text[start..self.pos]),
+            false => Err(self.error("expected a color")),
+        }
+    }
+
+    fn rounds(&mut self, palette: &mut Palette) -> Result<Vec<Cubes>, ParseError> {
+        let mut rounds = Vec::new();
+        let mut round = Cubes::default();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(b';') => {
+                    self.pos += 1;
+                    if round != Cubes::default() {
+                        rounds.push(round);
+                        round = Cubes::default();
+                    }
+                }
+                Some(b',') => self.pos += 1,
+                Some(b) if b.is_ascii_digit() => {
+                    let count = self.number()?;
+                    self.skip_whitespace();
+                    let start = self.pos;
+                    let color = palette.intern(self.word()?);
+                    if round.iter().any(|(c, _)| c == color) {
+                        return Err(ParseError {
+                            column: start + 1,
+                            ..self.error("color appears twice in one round")
+                        });
+                    }
+                    round.set(color, count);
+                    self.skip_whitespace();
+                    if !matches!(self.peek(), None | Some(b',') | Some(b';')) {
+                        return Err(self.error("expected `,` or `;`"));
+                    }
+                }
+                Some(_) => return Err(self.error("expected a cube count")),
+            }
+        }
+        if round != Cubes::default() {
+            rounds.push(round);
+        }
+        Ok(rounds)
+    }
+}
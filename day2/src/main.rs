@@ -1,151 +1,231 @@
-use std::{fs::read_to_string, path::PathBuf, str::from_utf8};
+mod bag;
+mod cubes;
+mod format;
+mod game;
+mod json;
+mod optimize;
+mod query;
+mod stream;
+use std::{
+    env,
+    fs::{read_to_string, File},
+    io::{stdin, BufReader},
+    path::PathBuf,
+    process::exit,
+};
+use cubes::{Cubes, Palette};
+use game::Game;
+use query::Query;
 
-#[derive(Default)]
-struct Cubes {
-    red: usize,
-    green: usize,
-    blue: usize,
+enum Output {
+    Text,
+    Json,
 }
 
-fn main() {
-    let input = PathBuf::from("input.txt");
-    let res_1 = part1(&input);
-    println!("{}", res_1);
-    let res_2 = part2(&input);
-    println!("{}", res_2);
-}
-
-fn part1(input: &PathBuf) -> usize {
-    let mut sum: usize = 0;
-    for line in read_to_string(input).unwrap().lines() {
-        let bytes = line.as_bytes();
-        let (game_id, game_bytes) = strip_game_id(bytes);
-        let rounds = strip_rounds(game_bytes);
-        let possible = rounds.iter().all(|round| match round {
-            round if round.red > 12 => false,
-            round if round.green > 13 => false,
-            round if round.blue > 14 => false,
-            _ => true,
-        });
-        if possible {
-            sum += game_id;
-        }
-    }
-    sum
+struct Config {
+    input: PathBuf,
+    bags: Vec<(String, Cubes)>,
+    palette: Palette,
+    query: Option<Query>,
+    format: Option<Output>,
+    watch: Option<bool>,
 }
 
-fn part2(input: &PathBuf) -> usize {
-    let mut sum: usize = 0;
-    for line in read_to_string(input).unwrap().lines() {
-        let bytes = line.as_bytes();
-        let (_, game_bytes) = strip_game_id(bytes);
-        let rounds = strip_rounds(game_bytes);
-        let mut min_bag = Cubes::default();
-        rounds.iter().for_each(|round| {
-            if round.red > min_bag.red {
-                min_bag = Cubes {
-                    red: round.red.clone(),
-                    ..min_bag
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config {
+            input: PathBuf::from("input.txt"),
+            bags: Vec::new(),
+            palette: Palette::default(),
+            query: None,
+            format: None,
+            watch: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => config.input = PathBuf::from(value(&arg, args.next())?),
+                "--bag" => {
+                    let spec = value(&arg, args.next())?;
+                    let bag = bag::parse_bag(&spec, &mut config.palette)?;
+                    config.bags.push((spec, bag));
                 }
-            }
-            if round.green > min_bag.green {
-                min_bag = Cubes {
-                    green: round.green.clone(),
-                    ..min_bag
+                "--bags" => {
+                    let path = PathBuf::from(value(&arg, args.next())?);
+                    let mut bags = bag::parse_bags_file(&path, &mut config.palette)?;
+                    config.bags.append(&mut bags);
                 }
+                "format" => config.format = Some(Output::Text),
+                "--json" => match config.format {
+                    Some(_) => config.format = Some(Output::Json),
+                    None => return Err("`--json` is only valid for `format`".to_string()),
+                },
+                "watch" => config.watch = Some(false),
+                "--follow" => match config.watch {
+                    Some(_) => config.watch = Some(true),
+                    None => return Err("`--follow` is only valid for `watch`".to_string()),
+                },
+                "query" => config.query = Some(Query::parse(&value(&arg, args.next())?)?),
+                _ => return Err(format!("unknown argument `{}`", arg)),
             }
-            if round.blue > min_bag.blue {
-                min_bag = Cubes {
-                    blue: round.blue.clone(),
-                    ..min_bag
-                }
-            }
-        });
-        sum += min_bag.red * min_bag.blue * min_bag.green;
+        }
+        Ok(config)
     }
-    sum
 }
 
-fn parse_digit(bytes: &[u8]) -> usize {
-    from_utf8(bytes).unwrap().parse::<usize>().unwrap()
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or(format!("`{}` expects a value", flag))
 }
 
-fn strip_game_id(bytes: &[u8]) -> (usize, &[u8]) {
-    match bytes {
-        [b'G', b'a', b'm', b'e', b' ', byte, b':', tail @ ..] => {
-            (parse_digit(&[byte.clone()]), tail)
+fn main() {
+    let mut config = Config::from_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: day2 [--input FILE] [--bag \"12 red, 13 green, 14 blue\"]... [--bags FILE]");
+        eprintln!("       day2 [--input FILE] query \"smallest-bag 3\"");
+        eprintln!("       day2 [--input FILE] format [--json]");
+        eprintln!("       day2 [--input FILE|-] [--bag SPEC] watch [--follow]");
+        exit(2)
+    });
+    if let Some(follow) = config.watch {
+        watch(config, follow);
+        return;
+    }
+    if let Some(output) = &config.format {
+        let games = load_games(&config.input, &mut config.palette);
+        match output {
+            Output::Text => print!("{}", format::format_games(&games, &config.palette)),
+            Output::Json => println!("{}", format::to_json(&games, &config.palette)),
         }
-        [b'G', b'a', b'm', b'e', b' ', byte_1, byte_2, b':', tail @ ..] => {
-            (parse_digit(&[byte_1.clone(), byte_2.clone()]), tail)
+        return;
+    }
+    if let Some(query) = &config.query {
+        let games = load_games(&config.input, &mut config.palette);
+        match query.evaluate(&games, &config.palette) {
+            Ok(answer) => println!("{}", answer),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1)
+            }
         }
-        [b'G', b'a', b'm', b'e', b' ', byte_1, byte_2, byte_3, b':', tail @ ..] => (
-            parse_digit(&[byte_1.clone(), byte_2.clone(), byte_3.clone()]),
-            tail,
-        ),
-        _ => panic!("Unable to fetch game id"),
+        return;
+    }
+    if config.bags.is_empty() {
+        let res_1 = part1(&config.input);
+        println!("{}", res_1);
+        let res_2 = part2(&config.input);
+        println!("{}", res_2);
+        return;
+    }
+    let bags: Vec<Cubes> = config.bags.iter().map(|(_, bag)| bag.clone()).collect();
+    let possible = possible_games(&config.input, &bags, &mut config.palette);
+    for ((spec, _), ids) in config.bags.iter().zip(possible) {
+        let list: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        println!("{}: [{}] sum {}", spec, list.join(", "), ids.iter().sum::<usize>());
     }
 }
 
-fn strip_rounds(bytes: &[u8]) -> Vec<Cubes> {
-    let mut rounds = Vec::new();
-    let mut remaining = bytes;
-    let mut round = Cubes::default();
-    let mut size: usize = 0;
-    loop {
-        match remaining {
-            [] => {
-                rounds.push(round);
-                break;
-            }
-            [b';', tail @ ..] => {
-                remaining = tail;
-                rounds.push(round);
-                round = Cubes::default();
-            }
-            [b',', tail @ ..] => {
-                remaining = tail;
-            }
-            [b' ', byte, b' ', tail @ ..] => {
-                size = parse_digit(&[byte.clone()]);
-                remaining = tail;
-            }
-            [b' ', byte_1, byte_2, b' ', tail @ ..] => {
-                size = parse_digit(&[byte_1.clone(), byte_2.clone()]);
-                remaining = tail;
-            }
+/// The evaluator behind `watch`, checking games against the first `--bag`
+/// or the puzzle's bag.
+fn evaluator(mut config: Config) -> stream::Evaluator {
+    let bag = match config.bags.first() {
+        Some((_, bag)) => bag.clone(),
+        None => bag::parse_bag(bag::DEFAULT_BAG, &mut config.palette).unwrap(),
+    };
+    stream::Evaluator::new(bag, config.palette)
+}
 
-            [b'r', b'e', b'd', tail @ ..] => {
-                remaining = tail;
-                round = Cubes {
-                    red: size.clone(),
-                    ..round
-                }
-            }
-            [b'b', b'l', b'u', b'e', tail @ ..] => {
-                remaining = tail;
-                round = Cubes {
-                    blue: size.clone(),
-                    ..round
-                }
-            }
-            [b'g', b'r', b'e', b'e', b'n', tail @ ..] => {
-                remaining = tail;
-                round = Cubes {
-                    green: size.clone(),
-                    ..round
-                }
-            }
-            _ => panic!("Unknown symbol!"),
-        }
+/// Evaluates a game log line by line, printing running totals after every
+/// game.
+fn watch(config: Config, follow: bool) {
+    let input = config.input.clone();
+    let mut evaluator = evaluator(config);
+    let on_line = |line: &str| match evaluator.push_line(line) {
+        Ok(Some(update)) => println!(
+            "game {}: {}, power {} | part1 {} part2 {} | max {}",
+            update.id,
+            if update.possible { "possible" } else { "impossible" },
+            update.power,
+            update.part1,
+            update.part2,
+            evaluator.maxima().describe(evaluator.palette())
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("{}", e),
+    };
+    let res = match input.to_str() {
+        Some("-") => stream::read_lines(stdin().lock(), follow, on_line),
+        _ => match File::open(&input) {
+            Ok(file) => stream::read_lines(BufReader::new(file), follow, on_line),
+            Err(e) => Err(e),
+        },
+    };
+    if let Err(e) = res {
+        eprintln!("{}: {}", input.display(), e);
+        exit(1)
     }
-    rounds
+}
+
+fn part1(input: &PathBuf) -> usize {
+    let mut palette = Palette::default();
+    let bag = bag::parse_bag(bag::DEFAULT_BAG, &mut palette).unwrap();
+    possible_games(input, &[bag], &mut palette)[0].iter().sum()
+}
+
+/// Reads a game log, either in the puzzle's text format or, for `.json`
+/// files, as exported by `format --json`. Exits with a message if the log
+/// is malformed.
+fn load_games(input: &PathBuf, palette: &mut Palette) -> Vec<Game> {
+    let text = read_to_string(input).unwrap();
+    let games = match input.extension().is_some_and(|ext| ext == "json") {
+        true => format::from_json(&text, palette).map_err(|e| format!("{}: {}", input.display(), e)),
+        false => game::parse_games(&text, palette).map_err(|e| format!("{}:{}", input.display(), e)),
+    };
+    games.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        exit(1)
+    })
+}
+
+/// IDs of the games that could have been played with each of `bags`.
+fn possible_games(input: &PathBuf, bags: &[Cubes], palette: &mut Palette) -> Vec<Vec<usize>> {
+    let games = load_games(input, palette);
+    bags.iter()
+        .map(|bag| {
+            games
+                .iter()
+                .filter(|game| game.is_possible(bag))
+                .map(|game| game.id)
+                .collect()
+        })
+        .collect()
+}
+
+fn part2(input: &PathBuf) -> usize {
+    let mut palette = Palette::default();
+    let games = load_games(input, &mut palette);
+    total_power(&games, &palette)
+}
+
+/// Part 2 over games already read with `palette`, which should hold only
+/// the log's colors.
+fn total_power(games: &[Game], palette: &Palette) -> usize {
+    games.iter().map(|game| game.min_bag().power(palette)).sum()
 }
 
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
 
-    use crate::{part1, part2};
+    use crate::{
+        bag::{parse_bag, parse_bags_file},
+        cubes::Palette,
+        format::{format_game, format_games, from_json, to_json},
+        game::{parse_game, parse_games, parse_round},
+        evaluator, part1, part2, possible_games, total_power, Config,
+        optimize::best_bag,
+        stream::{read_lines, Evaluator},
+        query::{color_stats, dominated_by, smallest_bag, Query},
+        load_games, Cubes, Game,
+    };
 
     #[test]
     fn problem_1() {
@@ -159,4 +239,323 @@ mod test {
         let sum = part2(&path);
         assert_eq!(2286, sum)
     }
+
+    #[test]
+    fn any_color() {
+        let mut palette = Palette::default();
+        let rounds = parse_game("Game 1: 3 yellow, 4 red; 12 purple", 1, &mut palette)
+            .unwrap()
+            .rounds;
+        let yellow = palette.intern("yellow");
+        let purple = palette.intern("purple");
+        let red = palette.intern("red");
+        assert_eq!(rounds.len(), 2);
+        assert_eq!((rounds[0].get(yellow), rounds[0].get(red), rounds[0].get(purple)), (3, 4, 0));
+        assert_eq!(rounds[1].get(purple), 12);
+
+        let min_bag = rounds[0].union(&rounds[1]);
+        assert_eq!(min_bag.power(&palette), 3 * 4 * 12);
+        assert!(!rounds[1].fits_in(&rounds[0]));
+    }
+
+    #[test]
+    fn extended_palette() {
+        let path = PathBuf::from("example_colors.txt");
+        assert_eq!(part1(&path), 4);
+        assert_eq!(part2(&path), 48 + 24);
+    }
+
+    #[test]
+    fn custom_bags() {
+        let path = PathBuf::from("example.txt");
+        let mut palette = Palette::default();
+        let bags = [
+            parse_bag("12 red, 13 green, 14 blue", &mut palette).unwrap(),
+            parse_bag("20 red,13 green,  15 blue", &mut palette).unwrap(),
+            parse_bag("100 red, 100 green", &mut palette).unwrap(),
+        ];
+        let possible = possible_games(&path, &bags, &mut palette);
+        assert_eq!(possible, vec![vec![1, 2, 5], vec![1, 2, 3, 4, 5], vec![]]);
+
+        let bags = parse_bags_file(&PathBuf::from("example_bags.txt"), &mut palette).unwrap();
+        assert_eq!(bags.len(), 2);
+        assert_eq!(bags[1].0, "20 red, 13 green, 15 blue");
+
+        assert!(parse_bag("12 red, green", &mut palette).is_err());
+        assert!(parse_bag("many red", &mut palette).is_err());
+    }
+
+    #[test]
+    fn flexible_games() {
+        let mut palette = Palette::default();
+        let red = palette.intern("red");
+        let blue = palette.intern("blue");
+
+        let game = parse_game("  Game   12345 :100 red,2 blue ;  ; 7  blue,;", 1, &mut palette).unwrap();
+        assert_eq!(game.id, 12345);
+        assert_eq!(game.rounds.len(), 2);
+        assert_eq!((game.rounds[0].get(red), game.rounds[0].get(blue)), (100, 2));
+        assert_eq!((game.rounds[1].get(red), game.rounds[1].get(blue)), (0, 7));
+
+        let game = parse_game("Game 7:", 1, &mut palette).unwrap();
+        assert_eq!(game.rounds, vec![]);
+
+        let games = parse_games("Game 1: 1 red\n\nGame 2: 2 red\n", &mut palette).unwrap();
+        assert_eq!(games.iter().map(|g| g.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        assert_eq!(parse_round(" 1000000 red ", &mut palette).unwrap().get(red), 1_000_000);
+    }
+
+    #[test]
+    fn positioned_errors() {
+        let mut palette = Palette::default();
+        let error = |text: &str| {
+            let e = parse_games(text, &mut Palette::default()).unwrap_err();
+            (e.line, e.column, e.message)
+        };
+        assert_eq!(error("Game 1: 1 red\nGane 2: 1 red"), (2, 1, "expected `Game`".to_string()));
+        assert_eq!(error("Game x: 1 red"), (1, 6, "expected a number".to_string()));
+        assert_eq!(error("Game 1 1 red"), (1, 8, "expected `:`".to_string()));
+        assert_eq!(error("Game 1: red"), (1, 9, "expected a cube count".to_string()));
+        assert_eq!(error("Game 1: 3 , 4 red"), (1, 11, "expected a color".to_string()));
+        assert_eq!(error("Game 1: 3 red 4 blue"), (1, 15, "expected `,` or `;`".to_string()));
+        assert_eq!(error("Game 1: 3 red, 4 red"), (1, 18, "color appears twice in one round".to_string()));
+        assert_eq!(
+            error("Game 99999999999999999999: 1 red"),
+            (1, 6, "number does not fit in usize".to_string())
+        );
+
+        assert_eq!(
+            parse_round("1 red; 2 red", &mut palette).unwrap_err().to_string(),
+            "1:13: expected a single round without `;`"
+        );
+    }
+
+    #[test]
+    fn queries() {
+        let path = PathBuf::from("example.txt");
+        let mut palette = Palette::default();
+        let games = load_games(&path, &mut palette);
+        let red = palette.get("red").unwrap();
+        let blue = palette.get("blue").unwrap();
+        let green = palette.get("green").unwrap();
+
+        let stats = color_stats(&games, red);
+        assert_eq!((stats.draws, stats.max), (11, 20));
+        assert!((stats.mean - 61.0 / 11.0).abs() < 1e-9);
+
+        // Game 2 draws as many blue as green cubes, so no color dominates it.
+        assert_eq!(dominated_by(&games, blue, &palette), vec![1]);
+        assert_eq!(dominated_by(&games, green, &palette), vec![3]);
+        assert_eq!(dominated_by(&games, red, &palette), vec![4, 5]);
+
+        // Min bags: (4 red, 2 green, 6 blue), (1, 3, 4), (20, 13, 6), (14, 3, 15), (6, 3, 2).
+        assert_eq!(smallest_bag(&games, 2, &palette).unwrap().total(), 13);
+        assert_eq!(smallest_bag(&games, 0, &palette).unwrap().total(), 0);
+        assert_eq!(smallest_bag(&games, 5, &palette).unwrap().total(), 20 + 13 + 15);
+        assert_eq!(smallest_bag(&games, 6, &palette), None);
+        for n in 0..=5 {
+            let bag = smallest_bag(&games, n, &palette).unwrap();
+            assert!(games.iter().filter(|g| g.is_possible(&bag)).count() >= n);
+            let min_bags = games.iter().map(|g| g.min_bag()).collect::<Vec<_>>();
+            let brute = (0..1 << games.len())
+                .filter(|mask: &usize| mask.count_ones() as usize == n)
+                .map(|mask| {
+                    (0..games.len())
+                        .filter(|i| mask & (1 << i) != 0)
+                        .fold(Default::default(), |bag: crate::Cubes, i| bag.union(&min_bags[i]))
+                        .total()
+                })
+                .min()
+                .unwrap();
+            assert_eq!(bag.total(), brute);
+        }
+
+        assert_eq!(Query::parse("max red").unwrap().evaluate(&games, &palette).unwrap(), "20");
+        assert_eq!(Query::parse("mean  green").unwrap().evaluate(&games, &palette).unwrap(), "3.69");
+        assert_eq!(Query::parse("dominated red").unwrap().evaluate(&games, &palette).unwrap(), "4, 5");
+        assert_eq!(
+            Query::parse("smallest-bag 5").unwrap().evaluate(&games, &palette).unwrap(),
+            "15 blue, 20 red, 13 green (48 cubes)"
+        );
+        assert!(Query::parse("max yellow").unwrap().evaluate(&games, &palette).is_err());
+        assert!(Query::parse("median red").is_err());
+    }
+
+    fn brute_force(games: &[Game], budget: usize, palette: &Palette) -> usize {
+        let colors: Vec<_> = palette.colors().collect();
+        let mut best = 0;
+        let mut counts = vec![0; colors.len()];
+        loop {
+            if counts.iter().sum::<usize>() <= budget {
+                let mut bag = Cubes::default();
+                colors.iter().zip(&counts).for_each(|(c, n)| bag.set(*c, *n));
+                let sum = games.iter().filter(|g| g.is_possible(&bag)).map(|g| g.id).sum();
+                best = best.max(sum);
+            }
+            let Some(idx) = counts.iter().position(|c| *c < budget) else {
+                return best;
+            };
+            counts[idx] += 1;
+            counts[..idx].iter_mut().for_each(|c| *c = 0);
+        }
+    }
+
+    #[test]
+    fn budget_optimizer() {
+        for file in ["example.txt", "example_colors.txt"] {
+            let mut palette = Palette::default();
+            let games = load_games(&PathBuf::from(file), &mut palette);
+            for budget in 0..=22 {
+                let (bag, sum) = best_bag(&games, budget, &palette);
+                assert!(bag.total() <= budget);
+                let possible: usize = games.iter().filter(|g| g.is_possible(&bag)).map(|g| g.id).sum();
+                assert_eq!(possible, sum);
+                assert_eq!(sum, brute_force(&games, budget, &palette), "{} with budget {}", file, budget);
+            }
+        }
+
+        let mut palette = Palette::default();
+        let games = load_games(&PathBuf::from("example.txt"), &mut palette);
+        assert_eq!(best_bag(&games, 0, &palette).1, 0);
+        assert_eq!(best_bag(&games, 13, &palette).1, 2 + 5);
+        assert_eq!(best_bag(&games, 1000, &palette).1, 15);
+        assert_eq!(
+            Query::parse("best-bag 13").unwrap().evaluate(&games, &palette).unwrap(),
+            "4 blue, 6 red, 3 green (13 cubes) makes games 2, 5 possible, sum 7"
+        );
+    }
+
+    #[test]
+    fn canonical_text() {
+        let mut palette = Palette::default();
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let games = parse_games(&text, &mut palette).unwrap();
+        let canonical = format_games(&games, &palette);
+        assert!(canonical.starts_with("Game 1: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green\n"));
+        let reparsed = parse_games(&canonical, &mut palette).unwrap();
+        assert_eq!(reparsed, games);
+        assert_eq!(format_games(&reparsed, &palette), canonical);
+
+        let game = parse_game("Game  07 :4 red,3   blue;; 1 green ;", 1, &mut palette).unwrap();
+        assert_eq!(format_game(&game, &palette), "Game 7: 3 blue, 4 red; 1 green");
+        assert_eq!(format_game(&Game { id: 3, rounds: vec![] }, &palette), "Game 3:");
+
+        let normalized = format_game(&game, &palette);
+        assert_eq!(parse_game(&normalized, 1, &mut palette).unwrap(), game);
+
+        // The same game with its colors mentioned in a different order, or
+        // after a bag interned them first, formats the same.
+        let mut first = Palette::default();
+        let mut second = Palette::default();
+        parse_bag("1 red", &mut second).unwrap();
+        let a = parse_games("Game 1: 1 red, 2 blue; 3 green", &mut first).unwrap();
+        let b = parse_games("Game 1: 2 blue, 1 red; 3 green", &mut second).unwrap();
+        assert_eq!(format_games(&a, &first), format_games(&b, &second));
+        assert_eq!(format_games(&a, &first), "Game 1: 2 blue, 1 red; 3 green\n");
+        assert_eq!(to_json(&a, &first), to_json(&b, &second));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut palette = Palette::default();
+        let games = load_games(&PathBuf::from("example_colors.txt"), &mut palette);
+        let json = to_json(&games, &palette);
+        assert!(json.starts_with(r#"[{"id":1,"rounds":[{"blue":3,"red":4,"yellow":1},"#));
+        assert_eq!(from_json(&json, &mut palette).unwrap(), games);
+
+        let mut fresh = Palette::default();
+        let imported = from_json(&json, &mut fresh).unwrap();
+        assert_eq!(format_games(&imported, &fresh), format_games(&games, &palette));
+
+        let pretty = r#"[ {"rounds": [ {"r\u00e9d": 2} ], "id": 4} ]"#;
+        let imported = from_json(pretty, &mut palette).unwrap();
+        assert_eq!(format_game(&imported[0], &palette), "Game 4: 2 réd");
+
+        assert!(from_json(r#"{"id":1}"#, &mut palette).is_err());
+        assert!(from_json(r#"[{"id":1}]"#, &mut palette).is_err());
+        assert!(from_json(r#"[{"id":1,"rounds":[{}]}]"#, &mut palette).is_err());
+        assert!(from_json(r#"[{"id":1,"rounds":[{"red":1,"red":2}]}]"#, &mut palette).is_err());
+        assert!(from_json(r#"[{"id":"1","rounds":[]}]"#, &mut palette).is_err());
+        assert!(from_json(r#"[] x"#, &mut palette).is_err());
+
+        let deep = "[".repeat(200_000);
+        assert_eq!(from_json(&deep, &mut palette).unwrap_err(), "byte 32: nested too deeply");
+        let nested = format!("{}{}", "[".repeat(32), "]".repeat(32));
+        assert_eq!(from_json(&nested, &mut palette).unwrap_err(), "game #1: expected an object");
+
+        // Names the text format cannot read back are rejected on import.
+        for name in ["a b;c", "", "x,y", "a:b", "tab\\t"] {
+            let json = format!(r#"[{{"id":1,"rounds":[{{"{}":1}}]}}]"#, name);
+            assert!(from_json(&json, &mut palette).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn streaming() {
+        let mut palette = Palette::default();
+        let bag = parse_bag("12 red, 13 green, 14 blue", &mut palette).unwrap();
+        let mut evaluator = Evaluator::new(bag, palette);
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let mut updates = Vec::new();
+        read_lines(text.as_bytes(), false, |line| {
+            updates.push(evaluator.push_line(line).unwrap().unwrap());
+        })
+        .unwrap();
+        let totals: Vec<_> = updates.iter().map(|u| (u.id, u.possible, u.part1, u.part2)).collect();
+        assert_eq!(
+            totals,
+            vec![(1, true, 1, 48), (2, true, 3, 60), (3, false, 3, 1620), (4, false, 3, 2250), (5, true, 8, 2286)]
+        );
+        assert_eq!(evaluator.maxima().describe(evaluator.palette()), "20 red, 13 green, 15 blue");
+
+        assert_eq!(evaluator.push_line("").unwrap(), None);
+        assert_eq!(evaluator.push_line("Game 6 1 red").unwrap_err().line, 7);
+        let update = evaluator.push_line("Game 8: 1 red, 1 green, 1 blue, 2 pink").unwrap().unwrap();
+        assert_eq!((update.power, update.part1, update.part2), (2, 8, 2));
+    }
+
+    #[test]
+    fn streaming_with_bag_colors() {
+        let args = ["--input", "example.txt", "--bag", "12 red, 13 green, 14 blue, 1 purple", "watch"];
+        let config = Config::from_args(args.iter().map(|a| a.to_string())).unwrap();
+        let mut evaluator = evaluator(config);
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let mut updates = Vec::new();
+        read_lines(text.as_bytes(), false, |line| {
+            updates.push(evaluator.push_line(line).unwrap().unwrap());
+        })
+        .unwrap();
+        let powers: Vec<_> = updates.iter().map(|u| u.power).collect();
+        assert_eq!(powers, vec![48, 12, 1560, 630, 36]);
+        assert_eq!(updates.last().unwrap().part2, part2(&PathBuf::from("example.txt")));
+
+        assert!(evaluator.push_line("Game 6: 1 teal, x").is_err());
+        assert_eq!(evaluator.palette().get("teal"), None);
+        let update = evaluator.push_line("Game 7: 2 red, 3 green, 1 blue").unwrap().unwrap();
+        assert_eq!((update.power, update.part2), (6, 2286 + 6));
+        let update = evaluator.push_line("Game 8: 1 red, 1 green, 1 blue, 2 teal").unwrap().unwrap();
+        assert_eq!((update.power, update.part2), (2, 2));
+    }
+
+    #[test]
+    fn streaming_zero_counts() {
+        let log = "Game 1: 0 red, 3 blue\nGame 2: 2 blue";
+        let mut palette = Palette::default();
+        let batch = total_power(&parse_games(log, &mut palette).unwrap(), &palette);
+
+        let config = Config::from_args(std::iter::empty()).unwrap();
+        let mut evaluator = evaluator(config);
+        let mut last = None;
+        read_lines(log.as_bytes(), false, |line| last = evaluator.push_line(line).unwrap()).unwrap();
+        assert_eq!(batch, 0);
+        assert_eq!(last.unwrap().part2, batch);
+    }
+
+    #[test]
+    fn partial_lines() {
+        let mut lines = Vec::new();
+        read_lines("a\r\nb\n\nc".as_bytes(), false, |line| lines.push(line.to_string())).unwrap();
+        assert_eq!(lines, vec!["a", "b", "", "c"]);
+    }
 }
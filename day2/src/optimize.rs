@@ -0,0 +1,69 @@
+use crate::{
+    cubes::{Color, Cubes, Palette},
+    game::Game,
+};
+
+/// Finds the bag of at most `budget` cubes that maximizes part 1's sum of
+/// possible game IDs, and returns it together with that sum.
+///
+/// A game is possible exactly when the bag covers its minimum bag, so each
+/// color of an optimal bag can be lowered to one of the games' minimum counts
+/// without losing a game. The search tries those candidates color by color,
+/// largest first, and drops any branch whose remaining games cannot beat the
+/// best sum found so far. It is exact, and fast for the handful of colors
+/// and the budgets the puzzle deals in.
+pub fn best_bag(games: &[Game], budget: usize, palette: &Palette) -> (Cubes, usize) {
+    let colors: Vec<Color> = palette.colors().collect();
+    let min_bags: Vec<(usize, Vec<usize>)> = games
+        .iter()
+        .map(|game| {
+            let bag = game.min_bag();
+            (game.id, colors.iter().map(|c| bag.get(*c)).collect::<Vec<_>>())
+        })
+        .filter(|(_, bag)| bag.iter().sum::<usize>() <= budget)
+        .collect();
+    let eligible: Vec<&(usize, Vec<usize>)> = min_bags.iter().collect();
+    let mut best = (0, vec![0; colors.len()]);
+    search(&eligible, budget, &mut Vec::new(), &mut best, colors.len());
+
+    let mut bag = Cubes::default();
+    for (color, count) in colors.iter().zip(best.1) {
+        bag.set(*color, count);
+    }
+    (bag, best.0)
+}
+
+fn search(
+    eligible: &[&(usize, Vec<usize>)],
+    budget: usize,
+    partial: &mut Vec<usize>,
+    best: &mut (usize, Vec<usize>),
+    colors: usize,
+) {
+    let sum: usize = eligible.iter().map(|(id, _)| id).sum();
+    if sum <= best.0 {
+        return;
+    }
+    let idx = partial.len();
+    if idx == colors {
+        *best = (sum, partial.clone());
+        return;
+    }
+    let mut values: Vec<usize> = eligible
+        .iter()
+        .map(|(_, bag)| bag[idx])
+        .filter(|count| *count <= budget)
+        .collect();
+    values.sort_unstable_by(|a, b| b.cmp(a));
+    values.dedup();
+    for count in values {
+        let next: Vec<&(usize, Vec<usize>)> = eligible
+            .iter()
+            .filter(|(_, bag)| bag[idx] <= count)
+            .copied()
+            .collect();
+        partial.push(count);
+        search(&next, budget - count, partial, best, colors);
+        partial.pop();
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    cubes::{Color, Cubes, Palette},
+    game::Game,
+    optimize::best_bag,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorStats {
+    /// Rounds in which the color was drawn at all.
+    pub draws: usize,
+    pub max: usize,
+    /// Mean count over the rounds in which the color was drawn.
+    pub mean: f64,
+}
+
+pub fn color_stats(games: &[Game], color: Color) -> ColorStats {
+    let counts: Vec<usize> = games
+        .iter()
+        .flat_map(|game| game.rounds.iter())
+        .filter_map(|round| round.iter().find(|(c, _)| *c == color).map(|(_, n)| n))
+        .collect();
+    let mean = match counts.len() {
+        0 => 0.0,
+        n => counts.iter().sum::<usize>() as f64 / n as f64,
+    };
+    ColorStats {
+        draws: counts.len(),
+        max: counts.iter().copied().max().unwrap_or(0),
+        mean,
+    }
+}
+
+/// IDs of the games in which `color` was drawn more, in total over all
+/// rounds, than any other color.
+pub fn dominated_by(games: &[Game], color: Color, palette: &Palette) -> Vec<usize> {
+    games
+        .iter()
+        .filter(|game| {
+            let total = |c: Color| game.rounds.iter().map(|round| round.get(c)).sum::<usize>();
+            let own = total(color);
+            own > 0 && palette.colors().all(|c| c == color || total(c) < own)
+        })
+        .map(|game| game.id)
+        .collect()
+}
+
+/// The bag with the fewest cubes in total that makes at least `n` games
+/// possible, or `None` if there are fewer than `n` games.
+///
+/// Each color of an optimal bag is one of the games' minimum counts for it,
+/// so the search walks those candidates color by color, pruning once the
+/// partial bag is no smaller than the best one found. The last color needs
+/// no search: it is the `n`-th smallest count among the games still in play.
+pub fn smallest_bag(games: &[Game], n: usize, palette: &Palette) -> Option<Cubes> {
+    if n > games.len() {
+        return None;
+    }
+    let colors: Vec<Color> = palette.colors().collect();
+    let min_bags: Vec<Vec<usize>> = games
+        .iter()
+        .map(|game| {
+            let bag = game.min_bag();
+            colors.iter().map(|c| bag.get(*c)).collect()
+        })
+        .collect();
+    let eligible: Vec<&[usize]> = min_bags.iter().map(|b| b.as_slice()).collect();
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    search(&eligible, n, &mut Vec::new(), 0, &mut best, colors.len());
+    best.map(|(_, counts)| {
+        let mut bag = Cubes::default();
+        for (color, count) in colors.iter().zip(counts) {
+            bag.set(*color, count);
+        }
+        bag
+    })
+}
+
+fn search(
+    eligible: &[&[usize]],
+    n: usize,
+    partial: &mut Vec<usize>,
+    total: usize,
+    best: &mut Option<(usize, Vec<usize>)>,
+    colors: usize,
+) {
+    let idx = partial.len();
+    if idx == colors {
+        if best.as_ref().is_none_or(|(t, _)| total < *t) {
+            *best = Some((total, partial.clone()));
+        }
+        return;
+    }
+    let mut values: Vec<usize> = eligible.iter().map(|bag| bag[idx]).collect();
+    values.sort_unstable();
+    if idx + 1 == colors {
+        let count = if n == 0 { 0 } else { values[n - 1] };
+        partial.push(count);
+        search(eligible, n, partial, total + count, best, colors);
+        partial.pop();
+        return;
+    }
+    values.dedup();
+    for count in std::iter::once(0).chain(values) {
+        if best.as_ref().is_some_and(|(t, _)| total + count >= *t) {
+            break;
+        }
+        let next: Vec<&[usize]> = eligible.iter().filter(|bag| bag[idx] <= count).copied().collect();
+        if next.len() < n {
+            continue;
+        }
+        partial.push(count);
+        search(&next, n, partial, total + count, best, colors);
+        partial.pop();
+    }
+}
+
+/// A question asked of a parsed game log from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Stats,
+    Max(String),
+    Mean(String),
+    SmallestBag(usize),
+    BestBag(usize),
+    Dominated(String),
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["stats"] => Ok(Query::Stats),
+            ["max", color] => Ok(Query::Max(color.to_string())),
+            ["mean", color] => Ok(Query::Mean(color.to_string())),
+            ["dominated", color] => Ok(Query::Dominated(color.to_string())),
+            ["smallest-bag", n] => n
+                .parse()
+                .map(Query::SmallestBag)
+                .map_err(|_| format!("`{}` is not a number of games", n)),
+            ["best-bag", budget] => budget
+                .parse()
+                .map(Query::BestBag)
+                .map_err(|_| format!("`{}` is not a number of cubes", budget)),
+            _ => Err(format!(
+                "unknown query `{}`; expected stats, max <color>, mean <color>, \
+                 dominated <color>, smallest-bag <n> or best-bag <budget>",
+                text
+            )),
+        }
+    }
+
+    pub fn evaluate(&self, games: &[Game], palette: &Palette) -> Result<String, String> {
+        let color = |name: &str| palette.get(name).ok_or(format!("no {} cubes were drawn", name));
+        match self {
+            Query::Stats => Ok(palette
+                .colors()
+                .map(|c| {
+                    let stats = color_stats(games, c);
+                    format!(
+                        "{}: {} draws, max {}, mean {:.2}",
+                        palette.name(c),
+                        stats.draws,
+                        stats.max,
+                        stats.mean
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            Query::Max(name) => Ok(color_stats(games, color(name)?).max.to_string()),
+            Query::Mean(name) => Ok(format!("{:.2}", color_stats(games, color(name)?).mean)),
+            Query::Dominated(name) => {
+                let ids = dominated_by(games, color(name)?, palette);
+                Ok(ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", "))
+            }
+            Query::SmallestBag(n) => match smallest_bag(games, *n, palette) {
+                Some(bag) => Ok(format!("{} ({} cubes)", bag.describe(palette), bag.total())),
+                None => Err(format!("there are only {} games", games.len())),
+            },
+            Query::BestBag(budget) => {
+                let (bag, sum) = best_bag(games, *budget, palette);
+                let ids: Vec<String> = games
+                    .iter()
+                    .filter(|game| game.is_possible(&bag))
+                    .map(|game| game.id.to_string())
+                    .collect();
+                Ok(format!(
+                    "{} ({} cubes) makes games {} possible, sum {}",
+                    bag.describe(palette),
+                    bag.total(),
+                    ids.join(", "),
+                    sum
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    collections::BTreeSet,
+    io::{BufRead, ErrorKind},
+    thread::sleep,
+    time::Duration,
+};
+use crate::{
+    cubes::{Color, Cubes, Palette},
+    game::{parse_game, ParseError},
+};
+
+/// What changed after one more game was read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    pub id: usize,
+    pub possible: bool,
+    pub power: usize,
+    pub part1: usize,
+    pub part2: usize,
+}
+
+/// Keeps part 1 and part 2 up to date as games arrive one at a time.
+///
+/// Powers are taken over every color named in the log so far, even with a
+/// count of zero, as batch mode does, so a color that shows up for the first time zeroes the power of
+/// every earlier game. The evaluator keeps each game's minimum bag to
+/// recompute part 2 when that happens. Colors that only appear in the bag
+/// do not count.
+pub struct Evaluator {
+    palette: Palette,
+    bag: Cubes,
+    /// Colors named by the games accepted so far.
+    colors: BTreeSet<Color>,
+    min_bags: Vec<Cubes>,
+    maxima: Cubes,
+    lines: usize,
+    part1: usize,
+    part2: usize,
+}
+
+impl Evaluator {
+    pub fn new(bag: Cubes, palette: Palette) -> Self {
+        Evaluator {
+            palette,
+            bag,
+            colors: BTreeSet::new(),
+            min_bags: Vec::new(),
+            maxima: Cubes::default(),
+            lines: 0,
+            part1: 0,
+            part2: 0,
+        }
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Largest count of each color drawn in any round so far.
+    pub fn maxima(&self) -> &Cubes {
+        &self.maxima
+    }
+
+    /// Feeds the next line of the log. Blank lines yield no update, and a
+    /// line that fails to parse leaves the evaluator as it was.
+    pub fn push_line(&mut self, line: &str) -> Result<Option<Update>, ParseError> {
+        self.lines += 1;
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        // Colors are interned as they are read, so parse into a copy of the
+        // palette and keep it only once the whole line parsed.
+        let mut palette = self.palette.clone();
+        let game = parse_game(line, self.lines, &mut palette)?;
+        self.palette = palette;
+        let min_bag = game.min_bag();
+        let possible = game.is_possible(&self.bag);
+        if possible {
+            self.part1 += game.id;
+        }
+        self.maxima = self.maxima.union(&min_bag);
+        let colors = self.colors.len();
+        self.colors.extend(game.rounds.iter().flat_map(|round| round.iter().map(|(color, _)| color)));
+        let seen = || self.colors.iter().copied();
+        let power = min_bag.power_over(seen());
+        self.part2 = match self.colors.len() == colors {
+            true => self.part2 + power,
+            false => self.min_bags.iter().map(|bag| bag.power_over(seen())).sum::<usize>() + power,
+        };
+        self.min_bags.push(min_bag);
+        Ok(Some(Update {
+            id: game.id,
+            possible,
+            power,
+            part1: self.part1,
+            part2: self.part2,
+        }))
+    }
+}
+
+/// Reads complete lines from `reader` and hands them to `on_line`. With
+/// `follow`, end of input means "wait for more" as in `tail -f`, and a
+/// trailing line without a newline is held back until it is finished.
+pub fn read_lines(
+    mut reader: impl BufRead,
+    follow: bool,
+    mut on_line: impl FnMut(&str),
+) -> std::io::Result<()> {
+    let mut line = String::new();
+    loop {
+        match reader.read_line(&mut line) {
+            Ok(0) if follow => sleep(Duration::from_millis(250)),
+            Ok(0) => {
+                if !line.is_empty() {
+                    on_line(&line);
+                }
+                return Ok(());
+            }
+            Ok(_) if line.ends_with('\n') => {
+                on_line(line.trim_end_matches(['\n', '\r']));
+                line.clear();
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt::Write;
+use crate::{
+    is_number,
+    rules::GearRule,
+    schematic::{Number, Schematic},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Symbol,
+    /// A number touching a symbol, counted in part 1.
+    Part,
+    /// A number touching no symbol.
+    Orphan,
+    /// A part that belongs to a gear.
+    Partner,
+    Gear,
+}
+
+impl Style {
+    fn ansi(self) -> &'static str {
+        match self {
+            Style::Plain => "",
+            Style::Symbol => "\x1b[1m",
+            Style::Part => "\x1b[32m",
+            Style::Orphan => "\x1b[31m",
+            Style::Partner => "\x1b[1;36m",
+            Style::Gear => "\x1b[1;30;43m",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Style::Plain => "",
+            Style::Symbol => "symbol",
+            Style::Part => "part",
+            Style::Orphan => "orphan",
+            Style::Partner => "partner",
+            Style::Gear => "gear",
+        }
+    }
+}
+
+/// Styles every cell of the schematic: parts and orphans, and for gears
+/// matching `rule`, the gear and its partners.
+pub fn styles(text: &str, schematic: &Schematic, rule: &GearRule) -> Vec<Vec<Style>> {
+    let mut styles: Vec<Vec<Style>> = text
+        .lines()
+        .map(|line| {
+            line.bytes()
+                .map(|b| match b {
+                    b'.' => Style::Plain,
+                    b if is_number(&b) => Style::Plain,
+                    _ => Style::Symbol,
+                })
+                .collect()
+        })
+        .collect();
+    let paint = |styles: &mut Vec<Vec<Style>>, number: &Number, style: Style| {
+        styles[number.id.row][number.id.start..number.id.end].fill(style);
+    };
+    for number in &schematic.numbers {
+        match schematic.touches_symbol(number) {
+            true => paint(&mut styles, number, Style::Part),
+            false => paint(&mut styles, number, Style::Orphan),
+        }
+    }
+    for (row, gear, parts) in rule.gears(schematic) {
+        parts.iter().for_each(|part| paint(&mut styles, part, Style::Partner));
+        styles[row][gear.pos] = Style::Gear;
+    }
+    styles
+}
+
+pub fn orphans(schematic: &Schematic) -> Vec<&Number> {
+    schematic
+        .numbers
+        .iter()
+        .filter(|number| !schematic.touches_symbol(number))
+        .collect()
+}
+
+/// Runs of equally styled bytes in each line.
+fn runs<'a>(text: &'a str, styles: &'a [Vec<Style>]) -> impl Iterator<Item = Vec<(Style, &'a str)>> {
+    text.lines().zip(styles).map(|(line, styles)| {
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for end in 1..=line.len() {
+            if end == line.len() || styles[end] != styles[start] {
+                runs.push((styles[start], &line[start..end]));
+                start = end;
+            }
+        }
+        runs
+    })
+}
+
+fn describe(number: &Number) -> String {
+    format!(
+        "{} at line {}, columns {}-{}",
+        number.val,
+        number.id.row + 1,
+        number.id.start + 1,
+        number.id.end
+    )
+}
+
+pub fn ansi(text: &str, schematic: &Schematic, rule: &GearRule) -> String {
+    let styles = styles(text, schematic, rule);
+    let mut out = String::new();
+    for line in runs(text, &styles) {
+        for (style, run) in line {
+            match style {
+                Style::Plain => out.push_str(run),
+                _ => write!(out, "{}{}\x1b[0m", style.ansi(), run).unwrap(),
+            }
+        }
+        out.push('\n');
+    }
+    writeln!(out, "\norphans:").unwrap();
+    for number in orphans(schematic) {
+        writeln!(out, "  {}", describe(number)).unwrap();
+    }
+    out
+}
+
+pub fn html(text: &str, schematic: &Schematic, rule: &GearRule) -> String {
+    let styles = styles(text, schematic, rule);
+    let mut out = String::from(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Engine schematic</title>\n",
+        "<style>\n",
+        "pre { font-family: monospace; }\n",
+        ".symbol { font-weight: bold; }\n",
+        ".part { color: green; }\n",
+        ".orphan { color: red; }\n",
+        ".partner { color: teal; font-weight: bold; }\n",
+        ".gear { background: gold; font-weight: bold; }\n",
+        "</style>\n</head>\n<body>\n<pre>\n"
+    ));
+    for line in runs(text, &styles) {
+        for (style, run) in line {
+            match style {
+                Style::Plain => out.push_str(&escape(run)),
+                _ => write!(out, "<span class=\"{}\">{}</span>", style.class(), escape(run)).unwrap(),
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("</pre>\n<h2>Orphans</h2>\n<ul>\n");
+    for number in orphans(schematic) {
+        writeln!(out, "<li>{}</li>", describe(number)).unwrap();
+    }
+    out.push_str("</ul>\n</body>\n</html>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
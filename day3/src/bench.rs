@@ -0,0 +1,53 @@
+use std::{hint::black_box, time::Instant};
+use crate::scan;
+
+/// Very wide schematic lines: runs of up to 19 digits separated by dots and
+/// the odd symbol, so every number fits in a `u64`.
+pub fn generate(lines: usize, width: usize) -> String {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut text = String::with_capacity(lines * (width + 1));
+    for _ in 0..lines {
+        let mut len = 0;
+        while len < width {
+            let run = (1 + next() % 19).min((width - len) as u64);
+            for _ in 0..run {
+                text.push((b'0' + (next() % 10) as u8) as char);
+            }
+            len += run as usize;
+            if len < width {
+                text.push(if next() % 8 == 0 { '*' } else { '.' });
+                len += 1;
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn measure(name: &str, text: &str, scan: impl Fn(&[u8]) -> u64) {
+    let start = Instant::now();
+    let sum: u64 = text.lines().map(|line| scan(black_box(line.as_bytes()))).fold(0, u64::wrapping_add);
+    let elapsed = start.elapsed();
+    let throughput = text.len() as f64 / elapsed.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<8} {:>10.2?} {:>10.1} MiB/s  (sum {})", name, elapsed, throughput, sum);
+}
+
+pub fn run(lines: usize, width: usize) {
+    let text = generate(lines, width);
+    println!("{} lines of {} bytes, {} MiB", lines, width, text.len() / (1024 * 1024));
+    measure("parse", &text, |line| {
+        line.split(|b| !b.is_ascii_digit())
+            .filter(|digits| !digits.is_empty())
+            .map(|digits| std::str::from_utf8(digits).unwrap().parse::<u64>().unwrap())
+            .fold(0, u64::wrapping_add)
+    });
+    measure("scan", &text, |line| {
+        scan::numbers(line).map(|span| span.unwrap().val).fold(0, u64::wrapping_add)
+    });
+}
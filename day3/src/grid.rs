@@ -0,0 +1,39 @@
+/// Every symbol of a grid of text in reading order, as `(row, col, byte)`.
+/// Anything but `.` and ASCII digits is a symbol. Once exhausted it stays
+/// exhausted; scan the grid again with a fresh iterator.
+pub struct Symbols<'a> {
+    rows: Vec<&'a [u8]>,
+    row: usize,
+    col: usize,
+}
+
+impl<'a> Symbols<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Symbols {
+            rows: text.lines().map(str::as_bytes).collect(),
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl Iterator for Symbols<'_> {
+    type Item = (usize, usize, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(line) = self.rows.get(self.row) {
+            match line[self.col..].iter().position(|b| *b != b'.' && !b.is_ascii_digit()) {
+                Some(offset) => {
+                    let col = self.col + offset;
+                    self.col = col + 1;
+                    return Some((self.row, col, line[col]));
+                }
+                None => {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,12 +1,15 @@
-use std::{collections::{VecDeque, HashSet}, hash::{Hash, Hasher}, fs::read_to_string, path::PathBuf, str::from_utf8};
-use std::collections::hash_map::DefaultHasher;
-
-#[derive(Default, Debug, PartialEq, Eq, Hash, Clone)]
-struct Part {
-    col: usize,
-    buf_hash: u64,
-    val: i32,
-}
+mod annotate;
+mod bench;
+mod grid;
+mod neighborhood;
+mod rules;
+mod scan;
+mod schematic;
+use std::{env, fs::read_to_string, path::{Path, PathBuf}, process::exit};
+use neighborhood::Neighborhood;
+use rules::{GearRule, Overflow};
+use scan::Minus;
+use schematic::Schematic;
 
 #[derive(Debug)]
 struct Symbol {
@@ -14,274 +17,399 @@ struct Symbol {
     val: u8
 }
 
-#[derive(Hash)]
-struct Buffer <'a> { 
-    buffer: &'a [u8], 
+enum Annotate {
+    Ansi,
+    Html,
 }
 
-struct Symbols <'a> { 
-    buf: &'a Buffer<'a>, 
-    pos: usize,
+struct Config {
+    input: PathBuf,
+    rules: Vec<(String, GearRule)>,
+    annotate: Option<Annotate>,
+    neighborhood: Neighborhood,
+    minus: Minus,
+    bench: Option<(usize, usize)>,
 }
 
-impl <'a> Iterator for Symbols <'a> {
-    type Item = Symbol;
-
-    fn next(&mut self) -> Option<Self::Item> { 
-        let pos = self.pos.clone();
-        self.pos += 1;
-        match &self.buf.buffer[(pos as usize)..] {
-            [] => {
-                self.pos = 0;
-                None
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config {
+            input: PathBuf::from("input.txt"),
+            rules: Vec::new(),
+            annotate: None,
+            neighborhood: Neighborhood::default(),
+            minus: Minus::default(),
+            bench: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => config.input = PathBuf::from(value(&arg, args.next())?),
+                "--rule" => {
+                    let spec = value(&arg, args.next())?;
+                    let rule = GearRule::parse(&spec)?;
+                    config.rules.push((spec, rule));
+                }
+                "--neighborhood" => {
+                    config.neighborhood = Neighborhood::parse(&value(&arg, args.next())?)?
+                }
+                "--minus" => config.minus = Minus::parse(&value(&arg, args.next())?)?,
+                "bench" => config.bench = Some((1_000, 100_000)),
+                "--lines" | "--width" => {
+                    let n = value(&arg, args.next())?
+                        .parse::<usize>()
+                        .map_err(|_| format!("`{}` expects a number", arg))?;
+                    match (&mut config.bench, arg.as_str()) {
+                        (Some((lines, _)), "--lines") => *lines = n,
+                        (Some((_, width)), _) => *width = n,
+                        _ => return Err(format!("`{}` is only valid for `bench`", arg)),
+                    }
+                }
+                "annotate" => config.annotate = Some(Annotate::Ansi),
+                "--html" => match config.annotate {
+                    Some(_) => config.annotate = Some(Annotate::Html),
+                    None => return Err("`--html` is only valid for `annotate`".to_string()),
+                },
+                _ => return Err(format!("unknown argument `{}`", arg)),
             }
-            [b'.', ..] => self.next(),
-            [byte, ..] if !is_number(byte) => Some(Symbol {pos, val: byte.clone()}),
-            _ => self.next(),
         }
+        Ok(config)
     }
 }
 
-struct Gears<'a> { 
-    buf: &'a Buffer<'a>, 
-    pos: usize,
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or(format!("`{}` expects a value", flag))
 }
 
-impl <'a> Iterator for Gears<'a> {
-    type Item = Symbol;
-
-    fn next(&mut self) -> Option<Self::Item> { 
-        let pos = self.pos.clone();
-        self.pos += 1;
-        match &self.buf.buffer[(pos as usize)..] {
-            [] => {
-                self.pos = 0;
-                None
-            }
-            [b'*', ..] => Some(Symbol {pos, val: b'*'}),
-            _ => self.next(),
+fn main() {
+    let config = Config::from_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: day3 [--input FILE] [--neighborhood 8|4|<chebyshev|manhattan>:<radius>[:wrap]]");
+        eprintln!("            [--minus symbol|sign|both]");
+        eprintln!("            [--rule <symbol>:<neighbors>:<product|sum>]... [annotate [--html]]");
+        eprintln!("       day3 bench [--lines N] [--width N]");
+        exit(2)
+    });
+    if let Some((lines, width)) = config.bench {
+        bench::run(lines, width);
+        return;
+    }
+    if let Some(annotate) = &config.annotate {
+        let text = read_to_string(&config.input).unwrap();
+        let schematic = load(&config.input, &config.neighborhood, config.minus);
+        let rule = config.rules.first().map(|(_, rule)| *rule).unwrap_or_default();
+        match annotate {
+            Annotate::Ansi => print!("{}", annotate::ansi(&text, &schematic, &rule)),
+            Annotate::Html => print!("{}", annotate::html(&text, &schematic, &rule)),
         }
+        return;
     }
-}
-
-fn main() {
-    let input = PathBuf::from("input.txt");
-    let res_1 = part1(&input);
+    if !config.rules.is_empty() {
+        let schematic = load(&config.input, &config.neighborhood, config.minus);
+        for (spec, rule) in &config.rules {
+            println!("{}: {}", spec, checked(&config.input, rule.evaluate(&schematic)));
+        }
+        return;
+    }
+    let schematic = load(&config.input, &config.neighborhood, config.minus);
+    let res_1 = checked(&config.input, part1(&schematic));
     println!("{}", res_1);
-    let res_2 = part2(&input);
+    let res_2 = checked(&config.input, part2(&schematic));
     println!("{}", res_2);
 }
 
-fn part1(input: &PathBuf) -> i32 {
-    let mut buffers: VecDeque<Buffer> = VecDeque::new();
-    let mut parts = HashSet::new(); 
-    for line in read_to_string(input).unwrap().lines() {
-        let bytes = line.as_bytes();
-        if buffers.len() == 3 {
-            buffers.pop_front();
-        }
-        buffers.push_back(Buffer { buffer: bytes});
-        if buffers.len() == 3 {
-            let _buffer = buffers.get(1).unwrap();
-            let symbols = Symbols { pos: 0, buf: _buffer}; 
-            for symbol in symbols { 
-                for buffer in &buffers {
-                    let pos = symbol.pos as isize;
-
-                    if let Some(number) = parse_number(pos-1, buffer) {
-                        parts.insert(number);
-                    }
-
-                    if let Some(number) = parse_number(pos, buffer) {
-                        parts.insert(number);
-                    }
-
-                    if let Some(number) = parse_number(pos+1, buffer) {
-                        parts.insert(number);
-                    }
-                }
-            }
-            
-        }
-    }
-    parts.iter().map(|p| p.val).sum()
+fn part1(schematic: &Schematic) -> Result<i128, Overflow> {
+    schematic
+        .numbers
+        .iter()
+        .filter(|number| schematic.touches_symbol(number))
+        .try_fold(0i128, |sum, number| {
+            sum.checked_add(number.val)
+                .ok_or(Overflow { row: number.id.row, col: number.id.start })
+        })
 }
 
-fn part2(input: &PathBuf) -> i32 {
-    let mut buffers: VecDeque<Buffer> = VecDeque::new();
-    let mut gear_ratio = 0; 
-    for line in read_to_string(input).unwrap().lines() {
-        let bytes = line.as_bytes();
-        if buffers.len() == 3 {
-            buffers.pop_front();
-        }
-        buffers.push_back(Buffer { buffer: bytes});
-        if buffers.len() == 3 {
-            let _buffer = buffers.get(1).unwrap();
-            let gears = Gears { pos: 0, buf: _buffer}; 
-            for gear in gears { 
-                let mut gear_parts = HashSet::new();
-                for buffer in &buffers {
-                    let pos = gear.pos as isize;
-
-                    if let Some(number) = parse_number(pos-1, buffer) {
-                        gear_parts.insert(number);
-                    }
-
-                    if let Some(number) = parse_number(pos, buffer) {
-                        gear_parts.insert(number);
-                    }
-
-                    if let Some(number) = parse_number(pos+1, buffer) {
-                        gear_parts.insert(number);
-                    }
-                }
-                if gear_parts.len() == 2 { 
-                    gear_ratio += gear_parts.into_iter().map(|p| p.val).reduce(|a,b| a*b).unwrap();
-                }
-            }
-        }
-    }
-    gear_ratio
+fn part2(schematic: &Schematic) -> Result<i128, Overflow> {
+    GearRule::default().evaluate(schematic)
 }
 
-fn parse_number(idx: isize, buf: &Buffer) -> Option<Part> {
-    if idx < 0 { 
-        return None;
-    }
-    if idx as usize >= buf.buffer.len() { 
-        return None;
-    }
-    let b = buf.buffer.get(idx as usize).unwrap();
-    if !is_number(b) { 
-        return None;
-    }
-    let (col, mut number) = get_preceding(idx as usize, buf.buffer);
-    number.append(&mut vec!(b.clone()));
-    number.append(&mut get_proceeding(idx as usize, buf.buffer));
-    let digit = parse_digit(&number[..]);
-    let mut hasher = DefaultHasher::new(); 
-    buf.hash(&mut hasher);
-    Some(Part { 
-        col,
-        buf_hash: hasher.finish(),
-        val: digit,
-    })
+fn is_number(byte: &u8) -> bool {
+    byte.is_ascii_digit()
 }
 
-fn get_preceding(idx: usize, buffer: &[u8]) -> (usize, Vec<u8>) {
-    if idx == 0 {
-        return (0, Vec::new());
-    }
-
-    match &buffer[(idx - 1)..] {
-        [b, ..] if is_number(b) => {
-            let (i, mut res) = get_preceding(idx-1, buffer);
-            let mut vec = vec!(b.clone());
-            res.append(&mut vec);
-            (i, res)
-        }
-        _ => (idx, Vec::new()),
-    }
-
+/// Unwraps a total, exiting with a message if it overflowed.
+fn checked(input: &Path, total: Result<i128, Overflow>) -> i128 {
+    total.unwrap_or_else(|e| {
+        eprintln!("{}:{}", input.display(), e);
+        exit(1)
+    })
 }
 
-fn get_proceeding(idx: usize, buffer: &[u8]) -> Vec<u8> {
-    if buffer.len() - 1 == idx {
-        return Vec::new();
-    }
-
-    match &buffer[(idx + 1)..] {
-        [b, ..] if is_number(b) => {
-            let mut vec = get_proceeding(idx+1, buffer);
-            let mut res = vec!(b.clone());
-            res.append(&mut vec);
-            res
+/// Reads the schematic, exiting with a message if a number overflows.
+fn load(input: &PathBuf, neighborhood: &Neighborhood, minus: Minus) -> Schematic {
+    let text = read_to_string(input).unwrap();
+    match Schematic::parse(&text, minus) {
+        Ok(schematic) => schematic.with_neighborhood(*neighborhood),
+        Err(e) => {
+            eprintln!("{}:{}", input.display(), e);
+            exit(1)
         }
-        _ => Vec::new(),
     }
-
-}
-
-fn is_number(byte: &u8) -> bool {
-    byte >= &b'0' && byte <= &b'9'
-}
-
-fn parse_digit(bytes: &[u8]) -> i32 {
-    from_utf8(bytes).unwrap().parse::<i32>().unwrap()
 }
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
-    use crate::{part1, part2, parse_number, Symbols, Buffer};
+    use crate::{part1, part2, grid::Symbols, Schematic, schematic::PartId};
+    use crate::{bench, scan::{self, Minus, Overflow, Span}};
+    use crate::rules::{self, Combine, GearRule};
+    use crate::annotate::{self, Style};
+    use crate::neighborhood::{Metric, Neighborhood};
+
+    fn example() -> Schematic {
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        Schematic::parse(&text, Minus::Symbol).unwrap()
+    }
 
     #[test]
     fn problem_1() {
-        let path = PathBuf::from("example.txt");
-        let sum = part1(&path);
+        let sum = part1(&example()).unwrap();
         assert_eq!(4361, sum)
     }
 
     #[test]
     fn problem_2() {
-        let path = PathBuf::from("example.txt");
-        let sum = part2(&path);
+        let sum = part2(&example()).unwrap();
         assert_eq!(467835, sum)
     }
 
     #[test]
     fn test_iter() {
-        let _buffer = [b'.', b'.', b'a', b'.', b'.', b'b', b'.'];
-        let buffer = Buffer {buffer: &_buffer};
-        let symbols = Symbols { pos: 0, buf: &buffer };
-        let mut iter = symbols.into_iter(); 
-        let mut symbol = iter.next();
-        assert_eq!(Some(b'a'), symbol.map(|s| s.val));
-        symbol = iter.next();
-        assert_eq!(Some(b'b'), symbol.map(|s| s.val));
-        symbol = iter.next();
-        assert_eq!(None, symbol.map(|s| s.val));
+        let mut iter = Symbols::new("..a..b.");
+        assert_eq!(Some((0, 2, b'a')), iter.next());
+        assert_eq!(Some((0, 5, b'b')), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+
+        let symbols: Vec<_> = Symbols::new("*..\n\n12#\n..$").collect();
+        assert_eq!(symbols, vec![(0, 0, b'*'), (2, 2, b'#'), (3, 2, b'$')]);
 
+        let long = format!("{}+\n{}", ".".repeat(1_000_000), ".".repeat(1_000_000));
+        assert_eq!(Symbols::new(&long).collect::<Vec<_>>(), vec![(0, 1_000_000, b'+')]);
     }
 
     #[test]
     fn parse_numbers() {
-        let mut  buffer = Buffer {buffer: &[b'1', b'0', b'1']};
-        let mut number = parse_number(1, &buffer).map(|p| p.val);
-        assert_eq!(number, Some(101));
+        let number = |line: &[u8], idx| {
+            scan::numbers(line)
+                .map(Result::unwrap)
+                .find(|span| (span.start..span.end).contains(&idx))
+                .map(|span| span.val)
+        };
+        assert_eq!(number(b"101", 1), Some(101));
+        assert_eq!(number(b"987", 0), Some(987));
+        assert_eq!(number(b".89", 1), Some(89));
+        assert_eq!(number(b".89", 2), Some(89));
+        assert_eq!(number(b".9.", 1), Some(9));
+        assert_eq!(number(b"...", 1), None);
+        assert_eq!(number(b".a.", 1), None);
+        assert_eq!(number(b"999", 3), None);
+        assert_eq!(number(b"18446744073709551615", 7), Some(u64::MAX));
+        assert_eq!(
+            scan::numbers(b"..18446744073709551616").next(),
+            Some(Err(Overflow { start: 2, end: 22 }))
+        );
+    }
 
-        buffer = Buffer {buffer: &[b'9', b'8', b'7']};
-        number = parse_number(0, &buffer).map(|p| p.val);
-        assert_eq!(number, Some(987));
+    #[test]
+    fn number_spans() {
+        let spans: Vec<_> = scan::numbers(b"467..114..*12").collect();
+        assert_eq!(spans, vec![
+            Ok(Span { start: 0, end: 3, val: 467, negative: false }),
+            Ok(Span { start: 5, end: 8, val: 114, negative: false }),
+            Ok(Span { start: 11, end: 13, val: 12, negative: false }),
+        ]);
+        assert_eq!(scan::numbers(b"..*..").count(), 0);
+
+        let text = bench::generate(3, 100_000);
+        for line in text.lines() {
+            let expected: Vec<u64> = line
+                .split(|c: char| !c.is_ascii_digit())
+                .filter(|digits| !digits.is_empty())
+                .map(|digits| digits.parse().unwrap())
+                .collect();
+            let found: Vec<u64> = scan::numbers(line.as_bytes()).map(|n| n.unwrap().val).collect();
+            assert_eq!(found, expected);
+        }
 
-        buffer = Buffer {buffer: &[b'.', b'8', b'9']};
-        number = parse_number(1, &buffer).map(|p| p.val);
-        assert_eq!(number, Some(89));
+        let err = Schematic::parse("1.\n.*99999999999999999999", Minus::Symbol).err().unwrap();
+        assert_eq!(err.to_string(), "2: number at columns 2..22 does not fit in u64");
+    }
 
-        buffer = Buffer {buffer: &[b'.', b'8', b'9']};
-        number = parse_number(2, &buffer).map(|p| p.val);
-        assert_eq!(number, Some(89));
+    #[test]
+    fn edge_rows() {
+        let text = "12*..\n.....\n..7..\n...#3";
+        let schematic = Schematic::parse(text, Minus::Symbol).unwrap();
+        let numbers: Vec<_> = schematic.numbers.iter().map(|n| (n.id.row, n.id.start, n.id.end, n.val)).collect();
+        assert_eq!(numbers, vec![(0, 0, 2, 12), (2, 2, 3, 7), (3, 4, 5, 3)]);
+        assert_eq!(schematic.symbols.iter().map(|(row, s)| (*row, s.pos, s.val)).collect::<Vec<_>>(),
+            vec![(0, 2, b'*'), (3, 3, b'#')]);
+        let parts: Vec<_> = schematic.numbers.iter().filter(|n| schematic.touches_symbol(n)).map(|n| n.val).collect();
+        assert_eq!(parts, vec![12, 7, 3]);
+    }
 
-        buffer = Buffer {buffer: &[b'.', b'9', b'.']};
-        number = parse_number(1, &buffer).map(|p| p.val);
-        assert_eq!(number, Some(9));
+    #[test]
+    fn edge_gears() {
+        let schematic = Schematic::parse("2*3\n...\n.4.\n5*.", Minus::Symbol).unwrap();
+        assert_eq!(part1(&schematic).unwrap(), 2 + 3 + 4 + 5);
+        assert_eq!(part2(&schematic).unwrap(), 6 + 20);
+    }
 
-        buffer = Buffer {buffer: &[b'.', b'.', b'.']};
-        number = parse_number(1, &buffer).map(|p| p.val);
-        assert_eq!(number, None);
+    #[test]
+    fn duplicated_rows() {
+        let schematic = Schematic::parse("12.\n.*.\n12.\n12.\n...\n.5.\n.#.\n.5.", Minus::Symbol).unwrap();
+        assert_eq!(part1(&schematic).unwrap(), 12 + 12 + 5 + 5);
+        assert_eq!(part2(&schematic).unwrap(), 12 * 12);
+
+        let ids: Vec<_> = schematic.numbers_around(1, 1).iter().map(|n| n.id).collect();
+        assert_eq!(ids, vec![PartId { row: 0, start: 0, end: 2 }, PartId { row: 2, start: 0, end: 2 }]);
+        assert_eq!(schematic.number_at(3, 1).map(|n| n.id), Some(PartId { row: 3, start: 0, end: 2 }));
+        assert_eq!(schematic.number_at(3, 2), None);
+        assert_eq!(schematic.number_at(4, 0), None);
+        assert_eq!(schematic.numbers_around(6, 1).len(), 2);
+    }
 
-        buffer = Buffer {buffer: &[b'.', b'a', b'.']};
-        number = parse_number(1, &buffer).map(|p| p.val);
-        assert_eq!(number, None);
+    #[test]
+    fn gear_rules() {
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let schematic = Schematic::parse(&text, Minus::Symbol).unwrap();
+        assert_eq!(GearRule::default().evaluate(&schematic).unwrap(), 467835);
+        assert_eq!(GearRule::parse("*:2:product").unwrap(), GearRule::default());
+        assert_eq!(GearRule::parse("*:2:sum").unwrap().evaluate(&schematic).unwrap(), 467 + 35 + 755 + 598);
+        assert_eq!(GearRule::parse("*:1:sum").unwrap().evaluate(&schematic).unwrap(), 617);
+        assert_eq!(GearRule::parse("#:1:product").unwrap().evaluate(&schematic).unwrap(), 633);
+        assert_eq!(GearRule::parse("$:1:sum").unwrap().evaluate(&schematic).unwrap(), 664);
+
+        let schematic = Schematic::parse("1.2\n.#.\n3..\n#..", Minus::Symbol).unwrap();
+        let rule = GearRule { symbol: b'#', neighbors: 3, combine: Combine::Sum };
+        assert_eq!(rule.evaluate(&schematic).unwrap(), 6);
+
+        assert!(GearRule::parse("#:3").is_err());
+        assert!(GearRule::parse("##:3:sum").is_err());
+        assert!(GearRule::parse("5:3:sum").is_err());
+        assert!(GearRule::parse("#:x:sum").is_err());
+        assert!(GearRule::parse("#:3:max").is_err());
+    }
 
-        buffer = Buffer {buffer: &[b'9', b'9', b'9']};
-        number = parse_number(-1, &buffer).map(|p| p.val);
-        assert_eq!(number, None);
+    #[test]
+    fn annotations() {
+        let text = "467..114..\n...*......\n..35..633.\n......#...";
+        let schematic = Schematic::parse(text, Minus::Symbol).unwrap();
+        let styles = annotate::styles(text, &schematic, &GearRule::default());
+        assert_eq!(styles[0][0..3], [Style::Partner; 3]);
+        assert_eq!(styles[0][5..8], [Style::Orphan; 3]);
+        assert_eq!(styles[1][3], Style::Gear);
+        assert_eq!(styles[2][6..9], [Style::Part; 3]);
+        assert_eq!(styles[3][6], Style::Symbol);
+        assert_eq!(styles[3][5], Style::Plain);
+
+        let orphans: Vec<_> = annotate::orphans(&schematic).iter().map(|n| (n.id, n.val)).collect();
+        assert_eq!(orphans, vec![(PartId { row: 0, start: 5, end: 8 }, 114)]);
+
+        let ansi = annotate::ansi(text, &schematic, &GearRule::default());
+        assert!(ansi.starts_with("\x1b[1;36m467\x1b[0m..\x1b[31m114\x1b[0m..\n"));
+        assert!(ansi.ends_with("orphans:\n  114 at line 1, columns 6-8\n"));
+
+        let html = annotate::html("1<&\n", &Schematic::parse("1<&\n", Minus::Symbol).unwrap(), &GearRule::default());
+        assert!(html.contains("<span class=\"part\">1</span><span class=\"symbol\">&lt;&amp;</span>\n"));
+    }
 
-        buffer = Buffer {buffer: &[b'9', b'9', b'9']};
-        number = parse_number(3, &buffer).map(|p| p.val);
-        assert_eq!(number, None);
+    #[test]
+    fn neighborhoods() {
+        let four = Neighborhood::parse("4").unwrap();
+        assert_eq!(four, Neighborhood { metric: Metric::Manhattan, radius: 1, wrap: false });
+        assert_eq!(four.offsets().len(), 4);
+        assert_eq!(Neighborhood::parse("8").unwrap(), Neighborhood::default());
+        assert_eq!(Neighborhood::parse("chebyshev:2").unwrap().offsets().len(), 24);
+        assert_eq!(Neighborhood::parse("manhattan:2:wrap").unwrap().offsets().len(), 12);
+        assert!(Neighborhood::parse("euclid:2").is_err());
+        assert!(Neighborhood::parse("6").is_err());
+
+        // 467, 592 and 755 only touch their symbols diagonally.
+        assert_eq!(part1(&example().with_neighborhood(four)).unwrap(), 35 + 633 + 617 + 664 + 598);
+        assert_eq!(part2(&example().with_neighborhood(four)).unwrap(), 0);
+
+        let wide = Neighborhood::parse("chebyshev:2").unwrap();
+        assert_eq!(part1(&example().with_neighborhood(wide)).unwrap(), 4361 + 114 + 58);
+
+        let text = "1..\n...\n..#";
+        let plain = Schematic::parse(text, Minus::Symbol).unwrap();
+        assert!(!plain.touches_symbol(&plain.numbers[0]));
+        let torus = Schematic::parse(text, Minus::Symbol).unwrap().with_neighborhood(Neighborhood::parse("8:wrap").unwrap());
+        assert!(torus.touches_symbol(&torus.numbers[0]));
+        assert_eq!(torus.numbers_around(2, 2).len(), 1);
+
+        // Huge radii stop at the edge of the schematic.
+        for spec in ["chebyshev:100000", "manhattan:100000:wrap"] {
+            let far = Schematic::parse(text, Minus::Symbol).unwrap().with_neighborhood(Neighborhood::parse(spec).unwrap());
+            assert!(far.touches_symbol(&far.numbers[0]));
+        }
+        assert_eq!(part1(&example().with_neighborhood(Neighborhood::parse("manhattan:100000").unwrap())).unwrap(), 4361 + 114 + 58);
     }
 
+    #[test]
+    fn signed_numbers() {
+        let spans: Vec<_> = scan::signed_numbers(b"-12.3-4.--5-").map(Result::unwrap).collect();
+        assert_eq!(spans, vec![
+            Span { start: 0, end: 3, val: 12, negative: true },
+            Span { start: 4, end: 5, val: 3, negative: false },
+            Span { start: 6, end: 7, val: 4, negative: false },
+            Span { start: 9, end: 11, val: 5, negative: true },
+        ]);
+        assert_eq!(Minus::parse("both"), Ok(Minus::Both));
+        assert!(Minus::parse("minus").is_err());
+
+        // The puzzle reading: `-` is a symbol, so 42 is a part.
+        let text = "...\n-42\n...";
+        let plain = Schematic::parse(text, Minus::Symbol).unwrap();
+        assert_eq!(plain.numbers[0].val, 42);
+        assert!(plain.touches_symbol(&plain.numbers[0]));
+
+        // As a sign it is neither a symbol nor, with `both`, a symbol for its
+        // own number.
+        let signed = Schematic::parse(text, Minus::Sign).unwrap();
+        assert_eq!(signed.numbers[0].val, -42);
+        assert_eq!(signed.numbers[0].id, PartId { row: 1, start: 0, end: 3 });
+        assert!(signed.symbols.is_empty());
+        assert!(!signed.touches_symbol(&signed.numbers[0]));
+        let both = Schematic::parse(text, Minus::Both).unwrap();
+        assert_eq!(both.symbols.len(), 1);
+        assert!(!both.touches_symbol(&both.numbers[0]));
+
+        let text = "5..\n.-7\n*..\n3.-";
+        let read = |minus| Schematic::parse(text, minus).unwrap();
+        // 5 touches the sign of -7, 3 touches the `*` and a stray `-`.
+        assert_eq!(part1(&read(Minus::Symbol)).unwrap(), 5 + 7 + 3);
+        assert_eq!(part1(&read(Minus::Sign)).unwrap(), -7 + 3);
+        assert_eq!(part1(&read(Minus::Both)).unwrap(), 5 - 7 + 3);
+        assert_eq!(part2(&read(Minus::Sign)).unwrap(), -7 * 3);
+
+        let schematic = Schematic::parse("12-34\n.-.\n.5.", Minus::Both).unwrap();
+        assert_eq!(schematic.numbers.iter().map(|n| n.val).collect::<Vec<_>>(), vec![12, 34, 5]);
+        let rule = GearRule::parse("-:2:sum").unwrap();
+        assert_eq!(rule.evaluate(&schematic).unwrap(), (12 + 34) + (12 + 5));
+    }
+
+    #[test]
+    fn overflowing_totals() {
+        let max = "18446744073709551615";
+        let schematic = Schematic::parse(&format!("{}*{}", max, max), Minus::Symbol).unwrap();
+        assert_eq!(part1(&schematic), Ok(2 * u64::MAX as i128));
+        assert_eq!(part2(&schematic), Err(rules::Overflow { row: 0, col: 20 }));
+        assert_eq!(part2(&schematic).unwrap_err().to_string(), "1:21: total does not fit in i128");
+
+        let rule = GearRule::parse("*:2:sum").unwrap();
+        assert_eq!(rule.evaluate(&schematic), Ok(2 * u64::MAX as i128));
+        let negative = Schematic::parse(&format!("-{}*{}", max, max), Minus::Sign).unwrap();
+        assert_eq!(rule.evaluate(&negative), Ok(0));
+        assert!(part2(&negative).is_err());
+    }
 }
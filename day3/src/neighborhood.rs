@@ -0,0 +1,74 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// Squares: every cell at most `radius` rows and columns away.
+    Chebyshev,
+    /// Diamonds: every cell at most `radius` steps away without diagonals.
+    Manhattan,
+}
+
+/// Which cells count as adjacent to a cell of the schematic. With `wrap`,
+/// the schematic is a torus and neighbors past one edge come back in at
+/// the opposite one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighborhood {
+    pub metric: Metric,
+    pub radius: usize,
+    pub wrap: bool,
+}
+
+impl Default for Neighborhood {
+    /// The puzzle's eight surrounding cells.
+    fn default() -> Self {
+        Neighborhood {
+            metric: Metric::Chebyshev,
+            radius: 1,
+            wrap: false,
+        }
+    }
+}
+
+impl Neighborhood {
+    /// Parses `8`, `4`, `chebyshev:<radius>` or `manhattan:<radius>`, each
+    /// optionally followed by `:wrap`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (spec, wrap) = match spec.strip_suffix(":wrap") {
+            Some(spec) => (spec, true),
+            None => (spec, false),
+        };
+        let (metric, radius) = match spec.split_once(':') {
+            None if spec == "8" => (Metric::Chebyshev, 1),
+            None if spec == "4" => (Metric::Manhattan, 1),
+            Some((metric, radius)) => {
+                let metric = match metric {
+                    "chebyshev" => Metric::Chebyshev,
+                    "manhattan" => Metric::Manhattan,
+                    _ => return Err(format!("unknown metric `{}`", metric)),
+                };
+                let radius = radius
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a radius", radius))?;
+                (metric, radius)
+            }
+            None => return Err(format!("unknown neighborhood `{}`", spec)),
+        };
+        Ok(Neighborhood { metric, radius, wrap })
+    }
+
+    /// Row and column offsets of every neighbor, the cell itself excluded.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let r = self.radius as isize;
+        let mut offsets = Vec::new();
+        for dr in -r..=r {
+            for dc in -r..=r {
+                let inside = match self.metric {
+                    Metric::Chebyshev => true,
+                    Metric::Manhattan => dr.abs() + dc.abs() <= r,
+                };
+                if inside && (dr, dc) != (0, 0) {
+                    offsets.push((dr, dc));
+                }
+            }
+        }
+        offsets
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+use crate::{
+    schematic::{Number, Schematic},
+    Symbol,
+};
+
+/// A total that no longer fits in an `i128`, at the cell of the gear or
+/// part that tipped it over. Rows and columns start at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: total does not fit in i128", self.row + 1, self.col + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+}
+
+/// Which symbols count as gears and how their parts are combined: a symbol
+/// qualifies when exactly `neighbors` distinct numbers touch it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GearRule {
+    pub symbol: u8,
+    pub neighbors: usize,
+    pub combine: Combine,
+}
+
+impl Default for GearRule {
+    /// The puzzle's gear ratio: `*` with two parts, multiplied.
+    fn default() -> Self {
+        GearRule {
+            symbol: b'*',
+            neighbors: 2,
+            combine: Combine::Product,
+        }
+    }
+}
+
+impl GearRule {
+    /// Parses `<symbol>:<neighbors>:<product|sum>`, e.g. `#:3:sum`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = spec.split(':').collect();
+        let [symbol, neighbors, combine] = fields.as_slice() else {
+            return Err(format!("expected `<symbol>:<neighbors>:<product|sum>`, got `{}`", spec));
+        };
+        let symbol = match symbol.as_bytes() {
+            [b] if *b != b'.' && !b.is_ascii_digit() => *b,
+            _ => return Err(format!("`{}` is not a single symbol", symbol)),
+        };
+        let neighbors = neighbors
+            .parse()
+            .map_err(|_| format!("`{}` is not a neighbor count", neighbors))?;
+        let combine = match *combine {
+            "product" => Combine::Product,
+            "sum" => Combine::Sum,
+            _ => return Err(format!("`{}` is neither `product` nor `sum`", combine)),
+        };
+        Ok(GearRule { symbol, neighbors, combine })
+    }
+
+    /// Every qualifying symbol with its row and the parts touching it.
+    pub fn gears<'a>(&self, schematic: &'a Schematic) -> Vec<(usize, &'a Symbol, Vec<&'a Number>)> {
+        schematic
+            .symbols_of(self.symbol)
+            .map(|(row, symbol)| (*row, symbol, schematic.numbers_around(*row, symbol.pos)))
+            .filter(|(_, _, parts)| parts.len() == self.neighbors)
+            .collect()
+    }
+
+    /// Sum over every qualifying symbol of its combined parts.
+    pub fn evaluate(&self, schematic: &Schematic) -> Result<i128, Overflow> {
+        self.gears(schematic)
+            .into_iter()
+            .try_fold(0i128, |total, (row, gear, parts)| {
+                let mut values = parts.iter().map(|part| part.val);
+                let combined = match self.combine {
+                    Combine::Product => values.try_fold(1i128, |acc, val| acc.checked_mul(val)),
+                    Combine::Sum => values.try_fold(0i128, |acc, val| acc.checked_add(val)),
+                };
+                combined
+                    .and_then(|combined| total.checked_add(combined))
+                    .ok_or(Overflow { row, col: gear.pos })
+            })
+    }
+}
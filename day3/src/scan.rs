@@ -0,0 +1,99 @@
+use std::fmt;
+
+/// How a `-` directly in front of digits is read. A `-` right after a digit
+/// or away from digits is always a symbol, so `12-34` stays two numbers
+/// around a symbol whichever way signs are read.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Minus {
+    /// Every `-` is a symbol, as in the puzzle.
+    #[default]
+    Symbol,
+    /// A leading `-` is the sign of its number and not a symbol.
+    Sign,
+    /// A leading `-` is the sign of its number and also a symbol for every
+    /// neighbor but that number.
+    Both,
+}
+
+impl Minus {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "symbol" => Ok(Minus::Symbol),
+            "sign" => Ok(Minus::Sign),
+            "both" => Ok(Minus::Both),
+            _ => Err(format!("`{}` is not one of `symbol`, `sign` or `both`", spec)),
+        }
+    }
+}
+
+/// A number at columns `start..end` of a line. A sign is part of the span
+/// and `val` holds the magnitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub val: u64,
+    pub negative: bool,
+}
+
+/// A run of digits at columns `start..end` too large for a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "number at columns {}..{} does not fit in u64", self.start, self.end)
+    }
+}
+
+fn parse(line: &[u8], start: usize, end: usize) -> Result<Span, Overflow> {
+    let negative = line[start] == b'-';
+    let mut val: u64 = 0;
+    for byte in &line[start + negative as usize..end] {
+        val = val
+            .checked_mul(10)
+            .and_then(|v| v.checked_add((byte - b'0') as u64))
+            .ok_or(Overflow { start, end })?;
+    }
+    Ok(Span { start, end, val, negative })
+}
+
+/// Every number in a line, left to right.
+pub struct Numbers<'a> {
+    line: &'a [u8],
+    pos: usize,
+    signed: bool,
+}
+
+pub fn numbers(line: &[u8]) -> Numbers<'_> {
+    Numbers { line, pos: 0, signed: false }
+}
+
+/// Like [`numbers`], but a `-` directly in front of digits, and not right
+/// after one, is taken as their sign.
+pub fn signed_numbers(line: &[u8]) -> Numbers<'_> {
+    Numbers { line, pos: 0, signed: true }
+}
+
+impl Iterator for Numbers<'_> {
+    type Item = Result<Span, Overflow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.line[self.pos..];
+        let mut start = self.pos + rest.iter().position(u8::is_ascii_digit)?;
+        if self.signed && start > 0 && self.line[start - 1] == b'-'
+            && (start == 1 || !self.line[start - 2].is_ascii_digit())
+        {
+            start -= 1;
+        }
+        let end = self.line[start + 1..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(self.line.len(), |pos| start + 1 + pos);
+        self.pos = end;
+        Some(parse(self.line, start, end))
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+use crate::{
+    grid::Symbols,
+    neighborhood::{Metric, Neighborhood},
+    scan::{self, Minus, Overflow},
+    Symbol,
+};
+
+/// Where a number is printed: columns `start..end` of `row`. Two numbers are
+/// the same part exactly when they share a position, whatever their digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartId {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub id: PartId,
+    /// Signed, so that the magnitude of every `u64` fits either way.
+    pub val: i128,
+}
+
+/// A number in the schematic that does not fit in a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub row: usize,
+    pub overflow: Overflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.row + 1, self.overflow)
+    }
+}
+
+/// The whole engine schematic, with every number and symbol indexed up
+/// front so that the first and last rows are handled like any other.
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    /// Every symbol with its row, in reading order.
+    pub symbols: Vec<(usize, Symbol)>,
+    pub height: usize,
+    /// Length of the longest row.
+    pub width: usize,
+    offsets: Vec<(isize, isize)>,
+    wrap: bool,
+}
+
+impl Schematic {
+    pub fn parse(text: &str, minus: Minus) -> Result<Self, ParseError> {
+        let rows: Vec<&[u8]> = text.lines().map(str::as_bytes).collect();
+        let mut numbers = Vec::new();
+        let mut signs = Vec::new();
+        for (row, line) in rows.iter().enumerate() {
+            let spans = match minus {
+                Minus::Symbol => scan::numbers(line),
+                Minus::Sign | Minus::Both => scan::signed_numbers(line),
+            };
+            for span in spans {
+                let span = span.map_err(|overflow| ParseError { row, overflow })?;
+                if span.negative {
+                    signs.push((row, span.start));
+                }
+                let id = PartId { row, start: span.start, end: span.end };
+                let val = span.val as i128;
+                numbers.push(Number { id, val: if span.negative { -val } else { val } });
+            }
+        }
+        let symbols = Symbols::new(text)
+            .filter(|&(row, col, _)| minus != Minus::Sign || signs.binary_search(&(row, col)).is_err())
+            .map(|(row, pos, val)| (row, Symbol { pos, val }))
+            .collect();
+        Ok(Schematic {
+            numbers,
+            symbols,
+            height: rows.len(),
+            width: rows.iter().map(|line| line.len()).max().unwrap_or(0),
+            offsets: Neighborhood::default().offsets(),
+            wrap: false,
+        })
+    }
+
+    /// Radii past the farthest cell on the schematic reach nothing new, so
+    /// they are cut down to it before any offsets are made.
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        let reach = match neighborhood.metric {
+            Metric::Chebyshev => self.height.max(self.width),
+            Metric::Manhattan => self.height + self.width,
+        };
+        self.offsets = Neighborhood { radius: neighborhood.radius.min(reach), ..neighborhood }.offsets();
+        self.wrap = neighborhood.wrap;
+        self
+    }
+
+    /// Cells adjacent to `(row, col)` that lie on the schematic.
+    fn cells_around(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets.iter().filter_map(move |(dr, dc)| {
+            let (r, c) = (row as isize + dr, col as isize + dc);
+            match self.wrap {
+                true => Some((
+                    r.rem_euclid(self.height as isize) as usize,
+                    c.rem_euclid(self.width as isize) as usize,
+                )),
+                false if r < 0 || c < 0 || r as usize >= self.height => None,
+                false => Some((r as usize, c as usize)),
+            }
+        })
+    }
+
+    pub fn symbol_at(&self, row: usize, col: usize) -> Option<&Symbol> {
+        self.symbols
+            .binary_search_by(|(r, symbol)| (*r, symbol.pos).cmp(&(row, col)))
+            .ok()
+            .map(|idx| &self.symbols[idx].1)
+    }
+
+    /// The number covering `(row, col)`, if any.
+    pub fn number_at(&self, row: usize, col: usize) -> Option<&Number> {
+        let idx = self.numbers.partition_point(|n| (n.id.row, n.id.start) <= (row, col));
+        let number = self.numbers.get(idx.checked_sub(1)?)?;
+        (number.id.row == row && col < number.id.end).then_some(number)
+    }
+
+    /// The distinct numbers in the neighborhood of `(row, col)`, leaving out
+    /// the number covering that cell when it is a sign.
+    pub fn numbers_around(&self, row: usize, col: usize) -> Vec<&Number> {
+        let own = self.number_at(row, col).map(|n| n.id);
+        let mut found: Vec<&Number> = Vec::new();
+        for (r, c) in self.cells_around(row, col) {
+            if let Some(number) = self.number_at(r, c) {
+                if Some(number.id) != own && !found.iter().any(|n| n.id == number.id) {
+                    found.push(number);
+                }
+            }
+        }
+        found
+    }
+
+    /// Every occurrence of one symbol, e.g. `b'*'` for gears.
+    pub fn symbols_of(&self, val: u8) -> impl Iterator<Item = &(usize, Symbol)> {
+        self.symbols.iter().filter(move |(_, symbol)| symbol.val == val)
+    }
+
+    /// Whether a symbol lies in the neighborhood of any digit of `number`.
+    /// Its own sign does not count.
+    pub fn touches_symbol(&self, number: &Number) -> bool {
+        let PartId { row, start, end } = number.id;
+        (start..end).any(|col| {
+            self.cells_around(row, col)
+                .filter(|&(r, c)| r != row || !(start..end).contains(&c))
+                .any(|(r, c)| self.symbol_at(r, c).is_some())
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use std::{hint::black_box, time::Instant};
+use crate::{bitset, card::Card};
+
+/// Puzzle-sized cards: 10 winning numbers and 25 we have, all distinct
+/// within a side and drawn from `1..=max`.
+pub fn generate(cards: usize, max: u32) -> Vec<Card> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut side = |len: usize| {
+        let mut numbers: Vec<u32> = Vec::with_capacity(len);
+        while numbers.len() < len {
+            let n = 1 + (next() % max as u64) as u32;
+            if !numbers.contains(&n) {
+                numbers.push(n);
+            }
+        }
+        numbers
+    };
+    (1..=cards)
+        .map(|id| Card { id, winning: side(10), have: side(25) })
+        .collect()
+}
+
+fn measure(name: &str, cards: &[Card], count: impl Fn(&[u32], &[u32]) -> usize) {
+    let start = Instant::now();
+    let matches: usize = cards
+        .iter()
+        .map(|card| count(black_box(&card.winning), black_box(&card.have)))
+        .sum();
+    let elapsed = start.elapsed();
+    let rate = cards.len() as f64 / elapsed.as_secs_f64() / 1e6;
+    println!("{:<8} {:>10.2?} {:>10.1} Mcards/s  (matches {})", name, elapsed, rate, matches);
+}
+
+pub fn run(cards: usize) {
+    for max in [99, 1_000] {
+        let pile = generate(cards, max);
+        println!("{} cards with numbers in 1..={}", cards, max);
+        measure("hashset", &pile, bitset::count_matches_hashed);
+        measure("bitset", &pile, bitset::count_matches);
+    }
+}
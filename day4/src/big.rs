@@ -0,0 +1,86 @@
+use std::fmt;
+use crate::rules::Count;
+
+/// A non-negative integer of any size, as little-endian base 2^32 limbs.
+/// Only what counting copies needs: small values, addition and printing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn add(&self, other: &BigUint) -> BigUint {
+        let (long, short) = match self.limbs.len() >= other.limbs.len() {
+            true => (&self.limbs, &other.limbs),
+            false => (&other.limbs, &self.limbs),
+        };
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0u64;
+        for (idx, limb) in long.iter().enumerate() {
+            let sum = *limb as u64 + short.get(idx).copied().unwrap_or(0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        let limbs = match n {
+            0 => Vec::new(),
+            n if n <= u32::MAX as u64 => vec![n as u32],
+            n => vec![n as u32, (n >> 32) as u32],
+        };
+        BigUint { limbs }
+    }
+}
+
+impl Count for BigUint {
+    fn zero() -> Self {
+        BigUint::from(0)
+    }
+
+    fn one() -> Self {
+        BigUint::from(1)
+    }
+
+    fn from_usize(n: usize) -> Self {
+        BigUint::from(n as u64)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self.add(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    /// Peels off nine decimal digits at a time by long division.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const CHUNK: u64 = 1_000_000_000;
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while limbs.iter().any(|limb| *limb != 0) {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let cur = (rem << 32) | *limb as u64;
+                *limb = (cur / CHUNK) as u32;
+                rem = cur % CHUNK;
+            }
+            chunks.push(rem);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                rest.iter().rev().try_for_each(|chunk| write!(f, "{:09}", chunk))
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+
+/// The numbers below 128 as bits of a `u128`, or `None` if any is larger.
+fn bits(numbers: &[u32]) -> Option<u128> {
+    numbers
+        .iter()
+        .try_fold(0u128, |bits, &n| (n < 128).then(|| bits | 1 << n))
+}
+
+/// How many of `have` are in `winning`. Puzzle numbers stay below 100, so
+/// both sides usually fit a `u128` and the count is a single popcount;
+/// larger numbers fall back to a `HashSet`. Neither side may repeat a number.
+pub fn count_matches(winning: &[u32], have: &[u32]) -> usize {
+    match (bits(winning), bits(have)) {
+        (Some(winning), Some(have)) => (winning & have).count_ones() as usize,
+        _ => count_matches_hashed(winning, have),
+    }
+}
+
+/// Intersects both sides as `HashSet`s, the fallback and benchmark baseline.
+pub fn count_matches_hashed(winning: &[u32], have: &[u32]) -> usize {
+    let winning: HashSet<u32> = winning.iter().copied().collect();
+    let have: HashSet<u32> = have.iter().copied().collect();
+    winning.intersection(&have).count()
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use crate::bitset;
+
+/// One scratchcard: the winning numbers left of the `|` and the numbers we
+/// have right of it, both in the order printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: usize,
+    pub winning: Vec<u32>,
+    pub have: Vec<u32>,
+}
+
+impl Card {
+    /// How many of our numbers are winning numbers.
+    pub fn matches(&self) -> usize {
+        bitset::count_matches(&self.winning, &self.have)
+    }
+}
+
+/// Where and why a pile of cards failed to parse. Lines and columns start
+/// at 1 and columns count bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every non-blank line as a card. Cards must be numbered 1, 2, 3, ...
+/// in order, since part 2 copies cards by their position.
+pub fn parse_cards(text: &str) -> Result<Vec<Card>, ParseError> {
+    let mut cards: Vec<Card> = Vec::new();
+    for (idx, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let card = parse_card(line, idx + 1)?;
+        let expected = cards.len() + 1;
+        if card.id != expected {
+            let id = line.trim_start().trim_start_matches("Card").trim_start();
+            return Err(error(line, idx + 1, id, format!("expected card {}, found card {}", expected, card.id)));
+        }
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+/// Parses `Card <id>: <winning numbers> | <numbers we have>`.
+pub fn parse_card(text: &str, line: usize) -> Result<Card, ParseError> {
+    let rest = text
+        .trim_start()
+        .strip_prefix("Card")
+        .ok_or_else(|| error(text, line, text.trim_start(), "expected `Card`"))?;
+    let (id, numbers) = rest
+        .split_once(':')
+        .ok_or_else(|| error(text, line, &text[text.len()..], "expected `:`"))?;
+    let id = id.trim();
+    let id = id
+        .parse::<usize>()
+        .map_err(|_| error(text, line, id, format!("`{}` is not a card id", id)))?;
+    let (winning, have) = numbers
+        .split_once('|')
+        .ok_or_else(|| error(text, line, &text[text.len()..], "expected `|`"))?;
+    Ok(Card {
+        id,
+        winning: parse_numbers(text, line, winning)?,
+        have: parse_numbers(text, line, have)?,
+    })
+}
+
+/// Parses one side of a card, rejecting numbers that appear twice.
+fn parse_numbers(text: &str, line: usize, side: &str) -> Result<Vec<u32>, ParseError> {
+    let mut numbers = Vec::new();
+    for token in side.split_whitespace() {
+        let n = token
+            .parse::<u32>()
+            .map_err(|_| error(text, line, token, format!("`{}` is not a number", token)))?;
+        if numbers.contains(&n) {
+            return Err(error(text, line, token, format!("{} appears twice on one side", n)));
+        }
+        numbers.push(n);
+    }
+    Ok(numbers)
+}
+
+/// An error pointing at `part`, which must be a slice of `text`.
+fn error(text: &str, line: usize, part: &str, message: impl Into<String>) -> ParseError {
+    ParseError {
+        line,
+        column: part.as_ptr() as usize - text.as_ptr() as usize + 1,
+        message: message.into(),
+    }
+}
@@ -1,102 +1,343 @@
-use std::{
-    collections::HashSet,
-    fs::read_to_string, 
-    path::PathBuf};
+mod bench;
+mod big;
+mod bitset;
+mod card;
+mod report;
+mod rules;
+use std::{env, fs::read_to_string, path::PathBuf, process::exit};
+use big::BigUint;
+use card::Card;
+use rules::{Overflow, Points, Scoring};
 
-fn main() {
-    let input = PathBuf::from("input.txt");
-    println!("{}", part1(&input));
-    println!("{}", part2(&input));
+enum Mode {
+    Solve,
+    Report { json: bool },
+    Bench { cards: usize },
 }
 
-fn parse_numbers<'a>(numbers: impl IntoIterator<Item=&'a str>) -> HashSet<i32> { 
-    numbers
-        .into_iter()
-        .filter_map(|n| n.parse::<i32>().ok())
-        .collect()
+struct Config {
+    input: PathBuf,
+    scoring: Scoring,
+    big: bool,
+    mode: Mode,
 }
 
-fn part1(input: &PathBuf) -> usize {
-    let mut res: usize = 0;
-    for line in read_to_string(input).unwrap().lines() {
-        let data = line.split(": ").last().unwrap();
-        let mut card = data.split(" | ");
-        let winning_numbers= parse_numbers(card.next().unwrap().split_whitespace());
-        let my_numbers = parse_numbers(card.next().unwrap().split_whitespace());
-        let my_winning_numbers = winning_numbers.intersection(&my_numbers); 
-        let base: usize = 2;
-        let val = match my_winning_numbers.count() as u32 { 
-            0 => 0,
-            p => base.pow(p-1)
+impl Config {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Config {
+            input: PathBuf::from("input.txt"),
+            scoring: Scoring::default(),
+            big: false,
+            mode: Mode::Solve,
         };
-        res += val;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => config.input = PathBuf::from(value(&arg, args.next())?),
+                "--points" => config.scoring.points = Points::parse(&value(&arg, args.next())?)?,
+                "--distance" => {
+                    config.scoring.distance = value(&arg, args.next())?
+                        .parse::<usize>()
+                        .map_err(|_| format!("`{}` expects a number", arg))?
+                }
+                "--big" => config.big = true,
+                "report" => config.mode = Mode::Report { json: false },
+                "--json" => match &mut config.mode {
+                    Mode::Report { json } => *json = true,
+                    _ => return Err("`--json` is only valid for `report`".to_string()),
+                },
+                "bench" => config.mode = Mode::Bench { cards: 2_000_000 },
+                "--cards" => {
+                    let n = value(&arg, args.next())?
+                        .parse::<usize>()
+                        .map_err(|_| format!("`{}` expects a number", arg))?;
+                    match &mut config.mode {
+                        Mode::Bench { cards } => *cards = n,
+                        _ => return Err(format!("`{}` is only valid for `bench`", arg)),
+                    }
+                }
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+        if config.big && matches!(config.mode, Mode::Report { .. }) {
+            return Err("`--big` is not supported with `report`".to_string());
+        }
+        Ok(config)
     }
-    res
 }
 
-struct Card { 
-    copies: usize,
-    winning_numbers: usize
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or(format!("`{}` expects a value", flag))
 }
 
-impl Card {
-    fn add_copies(&mut self, n: usize) { 
-        self.copies += n;
+fn main() {
+    let config = Config::from_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("usage: day4 [--input FILE] [--points geometric|linear|fibonacci|table:<p0>,<p1>,...]");
+        eprintln!("            [--distance N] [--big] [report [--json]]");
+        eprintln!("       day4 bench [--cards N]");
+        exit(2)
+    });
+    match config.mode {
+        Mode::Solve => {
+            let cards = load(&config.input);
+            let (res_1, res_2) = solve(&cards, &config.scoring, config.big);
+            for res in [res_1, res_2] {
+                match res {
+                    Ok(res) => println!("{}", res),
+                    Err(e) => {
+                        eprintln!("{}:{}; rerun with `--big`", config.input.display(), e);
+                        exit(1)
+                    }
+                }
+            }
+        }
+        Mode::Report { json } => {
+            let reports = report::build(&load(&config.input), &config.scoring).unwrap_or_else(|e| {
+                eprintln!("{}:{}", config.input.display(), e);
+                exit(1)
+            });
+            match json {
+                true => println!("{}", report::json(&reports)),
+                false => match report::table(&reports) {
+                    Ok(table) => print!("{}", table),
+                    Err(e) => {
+                        eprintln!("{}:{}", config.input.display(), e);
+                        exit(1)
+                    }
+                },
+            }
+        }
+        Mode::Bench { cards } => bench::run(cards),
     }
 }
 
-fn get_card_idx(card_str: &str) -> usize { 
-    let idx_str = card_str.split_whitespace().last().unwrap();
-    idx_str.parse::<usize>().unwrap()
+/// Reads the cards, exiting with a message if any line is malformed.
+fn load(input: &PathBuf) -> Vec<Card> {
+    let text = read_to_string(input).unwrap();
+    card::parse_cards(&text).unwrap_or_else(|e| {
+        eprintln!("{}:{}", input.display(), e);
+        exit(1)
+    })
 }
 
-fn part2(input: &PathBuf) -> usize {
-    let mut cards: Vec<Card> = Vec::new();
-    for line in read_to_string(input).unwrap().lines() {
-        let mut data = line.split(": ");
-        let mut card = data.last().unwrap().split(" | ");
-        let winning_numbers= parse_numbers(card.next().unwrap().split_whitespace());
-        let card_numbers = parse_numbers(card.next().unwrap().split_whitespace());
-        let card_winning_numbers = winning_numbers.intersection(&card_numbers); 
-        cards.push(Card {
-            copies: 1, 
-            winning_numbers: card_winning_numbers.count()
-        });
+/// Both parts, counted in `BigUint` with `big`.
+fn solve(cards: &[Card], scoring: &Scoring, big: bool) -> (Result<String, Overflow>, Result<String, Overflow>) {
+    match big {
+        true => (
+            part1_big(cards, scoring).map(|n| n.to_string()),
+            part2_big(cards, scoring).map(|n| n.to_string()),
+        ),
+        false => (
+            part1(cards, scoring).map(|n| n.to_string()),
+            part2(cards, scoring).map(|n| n.to_string()),
+        ),
     }
+}
 
-    for idx in 1..cards.len() { 
-        let card = &cards[idx - 1];
-        let copies = card.copies.clone();
-        // println!("copies: {} winning numbers: {}", copies, card.winning_numbers);
-        for i in idx..(idx + card.winning_numbers)
-        { 
-            if i < cards.len() { 
-                // println!("Adding {} copies to {}", copies, i);
-                let _card = &mut cards[i];
-                _card.add_copies(copies);
-            }
-        }
-    }
-    let res: usize = cards.iter().map(|c| c.copies ).sum();
-    // println!("{}", res);
-    res
+fn part1(cards: &[Card], scoring: &Scoring) -> Result<usize, Overflow> {
+    scoring.points(cards)
+}
+
+fn part1_big(cards: &[Card], scoring: &Scoring) -> Result<BigUint, Overflow> {
+    scoring.points(cards)
+}
+
+fn part2(cards: &[Card], scoring: &Scoring) -> Result<usize, Overflow> {
+    scoring.total(cards)
+}
+
+fn part2_big(cards: &[Card], scoring: &Scoring) -> Result<BigUint, Overflow> {
+    scoring.total(cards)
 }
 
 #[cfg(test)]
 mod test {
-    use std::path::PathBuf;
-    use crate::{part1, part2};
+    use crate::{bench, bitset, card, part1, part2, part2_big, report, solve, Config};
+    use crate::big::BigUint;
+    use crate::rules::{Overflow, Points, Scoring, Tally};
+
+    fn example() -> Vec<card::Card> {
+        card::parse_cards(&std::fs::read_to_string("example.txt").unwrap()).unwrap()
+    }
 
     #[test]
     fn problem_1() {
-        let path = PathBuf::from("example.txt");
-        let sum = part1(&path);
+        let sum = part1(&example(), &Scoring::default()).unwrap();
         assert_eq!(13, sum)
     }
     #[test]
     fn problem_2() {
-        let path = PathBuf::from("example.txt");
-        let sum = part2(&path);
+        let sum = part2(&example(), &Scoring::default()).unwrap();
         assert_eq!(30, sum)
     }
+
+    #[test]
+    fn parse_cards() {
+        let cards = card::parse_cards("Card   1: 41 48 | 83 41\n\nCard 2:  7 |\n").unwrap();
+        assert_eq!(cards[0], card::Card { id: 1, winning: vec![41, 48], have: vec![83, 41] });
+        assert_eq!(cards[0].matches(), 1);
+        assert_eq!(cards[1].have, Vec::<u32>::new());
+
+        let err = |text| card::parse_cards(text).unwrap_err().to_string();
+        assert_eq!(err("Card 1: 4 x | 5"), "1:11: `x` is not a number");
+        assert_eq!(err("Card 1: 4 -3 | 5"), "1:11: `-3` is not a number");
+        assert_eq!(err("Card 1: 4 5 | 5 6 5"), "1:19: 5 appears twice on one side");
+        assert_eq!(err("Card 1: 4 5 | 5\nCard 3: 1 | 2"), "2:6: expected card 2, found card 3");
+        assert_eq!(err("Card 2: 1 | 2"), "1:6: expected card 1, found card 2");
+        assert_eq!(err("Card 1: 4 5 6"), "1:14: expected `|`");
+        assert_eq!(err("Card one: 4 | 5"), "1:6: `one` is not a card id");
+        assert_eq!(err("Game 1: 4 | 5"), "1:1: expected `Card`");
+        assert_eq!(err("Card 1 4 | 5"), "1:13: expected `:`");
+    }
+
+    #[test]
+    fn bitset_matches() {
+        assert_eq!(bitset::count_matches(&[41, 48, 83, 86, 17], &[83, 86, 6, 31, 17, 9, 48, 53]), 4);
+        assert_eq!(bitset::count_matches(&[0, 127], &[127, 1]), 1);
+        assert_eq!(bitset::count_matches(&[128, 5], &[5, 128, 7]), 2);
+        assert_eq!(bitset::count_matches(&[], &[1]), 0);
+
+        for max in [99, 200] {
+            for card in bench::generate(1_000, max) {
+                assert_eq!(
+                    bitset::count_matches(&card.winning, &card.have),
+                    bitset::count_matches_hashed(&card.winning, &card.have)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scoring_rules() {
+        let score = |points: &Points| (0..6).map(|m| points.score::<usize>(m).unwrap()).collect::<Vec<_>>();
+        assert_eq!(score(&Points::Geometric), vec![0, 1, 2, 4, 8, 16]);
+        assert_eq!(score(&Points::Linear), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(score(&Points::Fibonacci), vec![0, 1, 2, 3, 5, 8]);
+        assert_eq!(score(&Points::parse("table:0, 10,15").unwrap()), vec![0, 10, 15, 15, 15, 15]);
+        assert_eq!(Points::parse("fibonacci"), Ok(Points::Fibonacci));
+        assert!(Points::parse("table:").is_err());
+        assert!(Points::parse("table:1,x").is_err());
+        assert!(Points::parse("quadratic").is_err());
+
+        // Matches on the example cards: 4, 2, 2, 1, 0, 0.
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let cards = card::parse_cards(&text).unwrap();
+        let linear = Scoring { points: Points::Linear, ..Default::default() };
+        assert_eq!(linear.points(&cards), Ok(4 + 2 + 2 + 1));
+        assert_eq!(Scoring { points: Points::Fibonacci, distance: 1 }.points(&cards), Ok(5 + 2 + 2 + 1));
+
+        assert_eq!(Points::Geometric.score::<usize>(64), Some(1 << 63));
+        assert_eq!(Points::Geometric.score::<usize>(65), None);
+        assert_eq!(Points::Fibonacci.score::<usize>(92), Some(12_200_160_415_121_876_738));
+        assert_eq!(Points::Fibonacci.score::<usize>(93), None);
+        let doubled = [card::Card { id: 1, winning: (1..=64).collect(), have: (1..=64).collect() }];
+        let pile = [doubled[0].clone(), card::Card { id: 2, ..doubled[0].clone() }];
+        assert_eq!(Scoring::default().points(&doubled), Ok(1 << 63));
+        assert_eq!(Scoring::default().points::<usize>(&pile), Err(Overflow { tally: Tally::Points, card: None }));
+        let wide = [card::Card { id: 1, winning: (1..=65).collect(), have: (1..=65).collect() }];
+        assert_eq!(Scoring::default().points::<usize>(&wide), Err(Overflow { tally: Tally::Points, card: Some(1) }));
+        assert_eq!(Overflow { tally: Tally::Points, card: Some(1) }.to_string(), "points of card 1 overflow");
+        assert_eq!(linear.copies::<usize>(&cards), Scoring::default().copies(&cards));
+
+        let far = Scoring { distance: 2, ..Default::default() };
+        assert_eq!(far.copies::<usize>(&cards), Ok(vec![1, 2, 4, 8, 16, 16]));
+    }
+
+    #[test]
+    fn big_copies() {
+        let big = |n: u64| BigUint::from(n);
+        assert_eq!(big(0).to_string(), "0");
+        assert_eq!(big(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(big(u64::MAX).add(&big(1)).to_string(), "18446744073709551616");
+        assert_eq!(big(u32::MAX as u64).add(&big(1)), big(1 << 32));
+
+        // With every card copying all the cards after it, card `i` ends up
+        // with 2^i copies and the total is 2^n - 1.
+        let text: String = (1..=200).map(|id| format!("Card {}: 1 | 1\n", id)).collect();
+        let cards = card::parse_cards(&text).unwrap();
+        let scoring = Scoring { distance: 200, ..Default::default() };
+        assert_eq!(part2(&cards, &scoring), Err(Overflow { tally: Tally::Copies, card: Some(65) }));
+        assert_eq!(
+            part2_big(&cards, &scoring).unwrap().to_string(),
+            "1606938044258990275541962092341162602522202993782792835301375"
+        );
+
+        assert_eq!(scoring.total::<usize>(&cards[..64]), Ok(usize::MAX));
+        // Without card 64 winning, card 65 only reaches 2^63 copies, but the
+        // total no longer fits.
+        let mut cards = cards[..65].to_vec();
+        cards[63].have = vec![2];
+        assert_eq!(scoring.copies::<usize>(&cards).unwrap()[64], 1 << 63);
+        assert_eq!(scoring.total::<usize>(&cards), Err(Overflow { tally: Tally::Copies, card: None }));
+        assert_eq!(Overflow { tally: Tally::Copies, card: Some(65) }.to_string(), "copies of card 65 overflow");
+    }
+
+    #[test]
+    fn card_report() {
+        let text = std::fs::read_to_string("example.txt").unwrap();
+        let cards = card::parse_cards(&text).unwrap();
+        let reports = report::build(&cards, &Scoring::default()).unwrap();
+        assert_eq!(reports[3], report::CardReport {
+            id: 4,
+            matches: 1,
+            points: 1,
+            copies: 8,
+            sources: vec![(1, 1), (2, 2), (3, 4)],
+        });
+        assert_eq!(reports.iter().map(|r| r.copies).sum::<usize>(), 30);
+        assert_eq!(reports.iter().map(|r| r.points).sum::<usize>(), 13);
+
+        let table = report::table(&reports).unwrap();
+        assert!(table.contains("     1        4         8         1  -\n"));
+        assert!(table.contains("     5        0         0        14  1:+1 3:+4 4:+8\n"));
+        assert!(table.ends_with("total: 13 points, 30 cards\n"));
+
+        assert_eq!(
+            report::json(&reports[4..]),
+            concat!(
+                "[{\"card\":5,\"matches\":0,\"points\":0,\"copies\":14,",
+                "\"from\":[{\"card\":1,\"copies\":1},{\"card\":3,\"copies\":4},{\"card\":4,\"copies\":8}]},",
+                "{\"card\":6,\"matches\":0,\"points\":0,\"copies\":1,\"from\":[]}]"
+            )
+        );
+    }
+
+    #[test]
+    fn big_points() {
+        // Thousands of cards matching 70 numbers each: 2^69 points apiece.
+        let numbers: Vec<String> = (1..=70).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let text: String = (1..=2000).map(|id| format!("Card {}: {} | {}\n", id, numbers, numbers)).collect();
+        let cards = card::parse_cards(&text).unwrap();
+
+        let config = |args: &[&str]| Config::from_args(args.iter().map(|a| a.to_string())).unwrap();
+        let plain = config(&[]);
+        let (res_1, res_2) = solve(&cards, &plain.scoring, plain.big);
+        assert_eq!(res_1, Err(Overflow { tally: Tally::Points, card: Some(1) }));
+        assert!(matches!(res_2, Err(Overflow { tally: Tally::Copies, .. })));
+
+        let big = config(&["--big"]);
+        let (res_1, res_2) = solve(&cards, &big.scoring, big.big);
+        assert_eq!(res_1.unwrap(), "1180591620717411303424000");
+        assert!(res_2.unwrap().len() > 20);
+
+        let (res_1, res_2) = solve(&cards[..3], &big.scoring, big.big);
+        assert_eq!(res_1.unwrap(), "1770887431076116955136");
+        assert_eq!(res_2.unwrap(), "7");
+    }
+
+    #[test]
+    fn report_overflow() {
+        // Two cards worth 2^63 points each: every card fits, the total does not.
+        let numbers: Vec<String> = (1..=64).map(|n| n.to_string()).collect();
+        let numbers = numbers.join(" ");
+        let text: String = (1..=2).map(|id| format!("Card {}: {} | {}\n", id, numbers, numbers)).collect();
+        let cards = card::parse_cards(&text).unwrap();
+        let reports = report::build(&cards, &Scoring::default()).unwrap();
+        assert_eq!(reports[1].points, 1 << 63);
+        assert_eq!(report::table(&reports), Err(Overflow { tally: Tally::Points, card: None }));
+
+        let args = ["--big", "report"].iter().map(|a| a.to_string());
+        assert_eq!(Config::from_args(args).err().unwrap(), "`--big` is not supported with `report`");
+    }
 }
@@ -0,0 +1,106 @@
+use std::fmt::Write;
+use crate::{
+    card::Card,
+    rules::{Overflow, Scoring, Tally},
+};
+
+/// How a single card fared: its score, and where its copies came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardReport {
+    pub id: usize,
+    pub matches: usize,
+    pub points: usize,
+    pub copies: usize,
+    /// Each earlier card that won copies of this one, with how many.
+    pub sources: Vec<(usize, usize)>,
+}
+
+pub fn build(cards: &[Card], scoring: &Scoring) -> Result<Vec<CardReport>, Overflow> {
+    let copies = scoring.copies::<usize>(cards)?;
+    let mut reports: Vec<CardReport> = cards
+        .iter()
+        .zip(&copies)
+        .map(|(card, copies)| {
+            let points = scoring.points.score::<usize>(card.matches()).ok_or(Overflow {
+                tally: Tally::Points,
+                card: Some(card.id),
+            })?;
+            Ok(CardReport {
+                id: card.id,
+                matches: card.matches(),
+                points,
+                copies: *copies,
+                sources: Vec::new(),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    for (idx, card) in cards.iter().enumerate() {
+        for report in &mut reports[scoring.reach(cards, idx)] {
+            report.sources.push((card.id, copies[idx]));
+        }
+    }
+    Ok(reports)
+}
+
+/// Total points and cards over `reports`.
+pub fn totals(reports: &[CardReport]) -> Result<(usize, usize), Overflow> {
+    let points = reports
+        .iter()
+        .try_fold(0usize, |sum, r| sum.checked_add(r.points))
+        .ok_or(Overflow { tally: Tally::Points, card: None })?;
+    let copies = reports
+        .iter()
+        .try_fold(0usize, |sum, r| sum.checked_add(r.copies))
+        .ok_or(Overflow { tally: Tally::Copies, card: None })?;
+    Ok((points, copies))
+}
+
+pub fn table(reports: &[CardReport]) -> Result<String, Overflow> {
+    let (points, copies) = totals(reports)?;
+    let mut out = String::new();
+    writeln!(out, "{:>6}  {:>7}  {:>8}  {:>8}  from", "card", "matches", "points", "copies").unwrap();
+    for report in reports {
+        let sources: Vec<String> = report
+            .sources
+            .iter()
+            .map(|(id, copies)| format!("{}:+{}", id, copies))
+            .collect();
+        writeln!(
+            out,
+            "{:>6}  {:>7}  {:>8}  {:>8}  {}",
+            report.id,
+            report.matches,
+            report.points,
+            report.copies,
+            match sources.is_empty() {
+                true => "-".to_string(),
+                false => sources.join(" "),
+            }
+        )
+        .unwrap();
+    }
+    writeln!(out, "total: {} points, {} cards", points, copies).unwrap();
+    Ok(out)
+}
+
+pub fn json(reports: &[CardReport]) -> String {
+    let cards: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let sources: Vec<String> = report
+                .sources
+                .iter()
+                .map(|(id, copies)| format!("{{\"card\":{},\"copies\":{}}}", id, copies))
+                .collect();
+            format!(
+                "{{\"card\":{},\"matches\":{},\"points\":{},\"copies\":{},\"from\":[{}]}}",
+                report.id,
+                report.matches,
+                report.points,
+                report.copies,
+                sources.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", cards.join(","))
+}
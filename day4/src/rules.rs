@@ -0,0 +1,166 @@
+use std::{fmt, ops::Range};
+use crate::card::Card;
+
+/// A count of points or copies, so both parts can run on `usize` or on
+/// `BigUint` when the counts grow past what a machine word holds.
+pub trait Count: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_usize(n: usize) -> Self;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
+
+impl Count for usize {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn from_usize(n: usize) -> Self {
+        n
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        usize::checked_add(*self, *other)
+    }
+}
+
+/// What is being counted when a count overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tally {
+    Points,
+    Copies,
+}
+
+/// The points or copies of a card, or their total when `card` is `None`,
+/// outgrew the counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub tally: Tally,
+    pub card: Option<usize>,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tally = match self.tally {
+            Tally::Points => "points",
+            Tally::Copies => "copies",
+        };
+        match self.card {
+            Some(id) => write!(f, "{} of card {} overflow", tally, id),
+            None => write!(f, "total {} overflow", tally),
+        }
+    }
+}
+
+/// Points a card scores for its number of matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Points {
+    /// 1, 2, 4, 8, ...: the puzzle's doubling.
+    Geometric,
+    /// 1, 2, 3, 4, ...
+    Linear,
+    /// 1, 2, 3, 5, 8, ...
+    Fibonacci,
+    /// Points for 0, 1, 2, ... matches; more matches than the table covers
+    /// score its last entry.
+    Table(Vec<usize>),
+}
+
+impl Points {
+    /// Parses `geometric`, `linear`, `fibonacci` or `table:<p0>,<p1>,...`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "geometric" => Ok(Points::Geometric),
+            "linear" => Ok(Points::Linear),
+            "fibonacci" => Ok(Points::Fibonacci),
+            _ => {
+                let table = spec.strip_prefix("table:").ok_or_else(|| {
+                    format!("expected `geometric`, `linear`, `fibonacci` or `table:<points>,...`, got `{}`", spec)
+                })?;
+                table
+                    .split(',')
+                    .map(|p| p.trim().parse().map_err(|_| format!("`{}` is not a number of points", p)))
+                    .collect::<Result<_, _>>()
+                    .map(Points::Table)
+            }
+        }
+    }
+
+    /// The points for `matches`, or `None` if they do not fit in a `C`.
+    pub fn score<C: Count>(&self, matches: usize) -> Option<C> {
+        match (self, matches) {
+            (Points::Table(table), _) => Some(C::from_usize(table[matches.min(table.len() - 1)])),
+            (_, 0) => Some(C::zero()),
+            (Points::Geometric, m) => (1..m).try_fold(C::one(), |p, _| p.checked_add(&p)),
+            (Points::Linear, m) => Some(C::from_usize(m)),
+            (Points::Fibonacci, m) => (1..m)
+                .try_fold((C::one(), C::one()), |(a, b), _| {
+                    let c = a.checked_add(&b)?;
+                    Some((b, c))
+                })
+                .map(|(_, b)| b),
+        }
+    }
+}
+
+/// A rule set for both parts: how matches score, and how far forward a
+/// card's matches copy: `distance` following cards per match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    pub points: Points,
+    pub distance: usize,
+}
+
+impl Default for Scoring {
+    /// The puzzle's rules: doubling points, one card copied per match.
+    fn default() -> Self {
+        Scoring { points: Points::Geometric, distance: 1 }
+    }
+}
+
+impl Scoring {
+    /// Every card's points, counted in `C`.
+    pub fn points<C: Count>(&self, cards: &[Card]) -> Result<C, Overflow> {
+        cards.iter().try_fold(C::zero(), |sum, card| {
+            let points = self.points.score::<C>(card.matches()).ok_or(Overflow {
+                tally: Tally::Points,
+                card: Some(card.id),
+            })?;
+            sum.checked_add(&points).ok_or(Overflow { tally: Tally::Points, card: None })
+        })
+    }
+
+    /// The positions of the cards that the card at `idx` wins copies of.
+    pub fn reach(&self, cards: &[Card], idx: usize) -> Range<usize> {
+        let reach = cards[idx].matches().saturating_mul(self.distance);
+        idx + 1..(idx + 1).saturating_add(reach).min(cards.len())
+    }
+
+    /// How many of each card we end up with, starting from one of each.
+    pub fn copies<C: Count>(&self, cards: &[Card]) -> Result<Vec<C>, Overflow> {
+        let mut copies = vec![C::one(); cards.len()];
+        for idx in 1..cards.len() {
+            let n = copies[idx - 1].clone();
+            let reach = self.reach(cards, idx - 1);
+            for (card, count) in cards[reach.clone()].iter().zip(&mut copies[reach]) {
+                *count = count.checked_add(&n).ok_or(Overflow {
+                    tally: Tally::Copies,
+                    card: Some(card.id),
+                })?;
+            }
+        }
+        Ok(copies)
+    }
+
+    /// Every card we end up with, counted in `C`.
+    pub fn total<C: Count>(&self, cards: &[Card]) -> Result<C, Overflow> {
+        self.copies::<C>(cards)?
+            .iter()
+            .try_fold(C::zero(), |sum, count| sum.checked_add(count))
+            .ok_or(Overflow { tally: Tally::Copies, card: None })
+    }
+}